@@ -1,14 +1,16 @@
 use std::{fs, ops::Range, time::Duration};
 
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
-use nfdeterminize::automaton::{AlgorithmKind, Automaton};
+use nfdeterminize::automaton::{AlgorithmKind, Automaton, AutomatonType};
+use nfdeterminize::state_set::RoaringStateSet;
 use nfdeterminize::transition_graphs::{get_buffer_and_stack_aut, get_two_stack_aut};
 
 const N_THREADS: usize = 12;
 
-const AUT_KINDS: [AlgorithmKind; 2] = [
+const AUT_KINDS: [AlgorithmKind; 3] = [
     AlgorithmKind::Sequential,
     AlgorithmKind::Multithreaded(N_THREADS),
+    AlgorithmKind::Rayon,
 ];
 
 const NUM_BNS_BUFFERS: Range<usize> = 2..4;
@@ -20,6 +22,9 @@ const NUM_TWO_STACK_STACK1: Range<usize> = 2..6;
 const NUM_GAP_BUFFERS: Range<usize> = 2..4;
 const NUM_GAP_STACKS: Range<usize> = 2..6;
 const AUTOMATONS_PATH: &str = "automatons/";
+const WIDE_ALPHABET_SIZES: [usize; 3] = [16, 64, 256];
+const WIDE_ALPHABET_STATES: usize = 12;
+const DETERMINISTIC_NFA_SIZES: [usize; 3] = [100, 1000, 10000];
 
 // Comparative benches
 fn run_bns_benchmark(c: &mut Criterion) {
@@ -54,6 +59,35 @@ fn run_two_stack_benchmark(c: &mut Criterion) {
         }
     }
 }
+fn run_state_set_benchmark(c: &mut Criterion) {
+    for k in AUT_KINDS {
+        for i in NUM_TWO_STACK_STACK0 {
+            for j in NUM_TWO_STACK_STACK1 {
+                let automaton = get_two_stack_aut(i, j);
+                c.bench_function(&format!("determinize two-stack {i} {j} {k:?} ubig"), |b| {
+                    b.iter(|| automaton.determinized(k))
+                });
+                c.bench_function(
+                    &format!("determinize two-stack {i} {j} {k:?} roaring"),
+                    |b| b.iter(|| automaton.determinized_with::<RoaringStateSet>(k)),
+                );
+            }
+        }
+    }
+}
+fn run_trie_benchmark(c: &mut Criterion) {
+    for i in NUM_BNS_BUFFERS {
+        for j in NUM_BNS_STACKS {
+            let automaton = get_buffer_and_stack_aut(i, j);
+            c.bench_function(&format!("determinize bns {i} {j} hashmap"), |b| {
+                b.iter(|| automaton.determinized(AlgorithmKind::Sequential))
+            });
+            c.bench_function(&format!("determinize bns {i} {j} trie"), |b| {
+                b.iter(|| automaton.determinized_trie())
+            });
+        }
+    }
+}
 fn run_gap_benchmarks(c: &mut Criterion) {
     for k in AUT_KINDS {
         for i in NUM_GAP_BUFFERS {
@@ -74,6 +108,51 @@ fn run_gap_benchmarks(c: &mut Criterion) {
         }
     }
 }
+fn run_wide_alphabet_benchmark(c: &mut Criterion) {
+    for k in AUT_KINDS {
+        for alphabet in WIDE_ALPHABET_SIZES {
+            let automaton = Automaton::new(
+                AutomatonType::NonDet,
+                WIDE_ALPHABET_STATES,
+                alphabet,
+                (0..WIDE_ALPHABET_STATES)
+                    .flat_map(|s| {
+                        (1..alphabet + 1).flat_map(move |a| {
+                            vec![
+                                (s, a, (s * a + 1) % WIDE_ALPHABET_STATES),
+                                (s, a, (s + a) % WIDE_ALPHABET_STATES),
+                            ]
+                        })
+                    })
+                    .collect(),
+                vec![0],
+                vec![WIDE_ALPHABET_STATES - 1],
+            );
+            c.bench_function(&format!("determinize wide {alphabet} {k:?}"), |b| {
+                b.iter(|| automaton.determinized(k))
+            });
+        }
+    }
+}
+fn run_deterministic_nfa_benchmark(c: &mut Criterion) {
+    for k in AUT_KINDS {
+        for size in DETERMINISTIC_NFA_SIZES {
+            let automaton = Automaton::new(
+                AutomatonType::NonDet,
+                size,
+                2,
+                (0..size)
+                    .flat_map(|s| vec![(s, 1, (s + 1) % size), (s, 2, 0)])
+                    .collect(),
+                vec![0],
+                vec![size - 1],
+            );
+            c.bench_function(&format!("determinize deterministic {size} {k:?}"), |b| {
+                b.iter(|| automaton.determinized(k))
+            });
+        }
+    }
+}
 fn run_mt_increase(c: &mut Criterion) {
     for k in 2..N_THREADS {
         let automaton = get_buffer_and_stack_aut(BNS_MT_INCREASE.0, BNS_MT_INCREASE.1);
@@ -98,6 +177,6 @@ fn run_mt_increase(c: &mut Criterion) {
 criterion_group! {
     name = benches;
     config = Criterion::default().significance_level(0.05).sample_size(25).measurement_time(Duration::new(5, 0));
-    targets = run_bns_benchmark, run_two_stack_benchmark, run_gap_benchmarks, run_mt_increase
+    targets = run_bns_benchmark, run_two_stack_benchmark, run_state_set_benchmark, run_trie_benchmark, run_gap_benchmarks, run_wide_alphabet_benchmark, run_deterministic_nfa_benchmark, run_mt_increase
 }
 criterion_main!(benches);
@@ -1,10 +1,22 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::io::{self, Write};
+use std::sync::atomic::AtomicBool;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::automaton_multithreaded::rabin_scott_mt;
-use crate::automaton_sequential::{hopcroft_algo, rabin_scott_seq};
+use crate::automaton_rayon::rabin_scott_rayon;
+use crate::automaton_sequential::{
+    hopcroft_algo, hopcroft_algo_with, rabin_scott_fused, rabin_scott_seq, rabin_scott_seq_trie,
+    rabin_scott_seq_with, rabin_scott_trace, CancellableFrontier, Determinization, Frontier,
+    StatsFrontier,
+};
+use crate::spill_queue::SpillQueue;
+use crate::state_set::StateSet;
 use crate::ubig::Ubig;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AutomatonType {
     Det,
     NonDet,
@@ -12,6 +24,7 @@ pub enum AutomatonType {
 
 // Structure for an automaton.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Automaton {
     pub automaton_type: AutomatonType,
     pub size: usize,
@@ -19,14 +32,105 @@ pub struct Automaton {
     pub table: Vec<(usize, usize, usize)>,
     pub start: Vec<usize>,
     pub end: Vec<usize>,
+    /// Priority of each accept state, aligned with `end` - Lower numbers take precedence.
+    pub accept_priority: Option<Vec<usize>>,
+    /// Probability of each transition, aligned with `table`.
+    pub probabilities: Option<Vec<f64>>,
+    /// Empty char transitions `(source, target)` kept apart from `table` - They are treated as
+    /// letter 0 transitions of the table.
+    pub epsilon_table: Option<Vec<(usize, usize)>>,
+    /// Name of each letter, letter `i` being named by `alphabet_labels[i - 1]` - Letters are
+    /// written as their number when there are no labels.
+    pub alphabet_labels: Option<Vec<String>>,
 }
 
+/// Number of subsets explored to estimate the size of a determinization before choosing how to
+/// run it.
+const AUTO_PROBE_LIMIT: usize = 64;
+/// Estimated subsets times NFA states above which multithreaded determinization is chosen -
+/// Below it, the BnS benches run faster sequentially as spawning threads dominates.
+const AUTO_MULTITHREADED_WORK: usize = 2_000_000;
+/// Number of DFA states explored by the fused determinization between two merges of the
+/// equivalent states discovered so far.
+const FUSED_MERGE_INTERVAL: usize = 1024;
+
+/// Sorted transitions, start states and accept states of an automaton.
+pub type SortedTransitions = (Vec<(usize, usize, usize)>, Vec<usize>, Vec<usize>);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum AlgorithmKind {
     /// Run command sequentially
     Sequential,
     /// Run command in multithreaded mode
     Multithreaded(usize),
+    /// Run command on the rayon thread pool
+    Rayon,
+}
+
+/// A step of the superset construction - The subset of states reached by reading a letter from
+/// another subset, along with their DFA state ids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeterminizeStep {
+    pub source: usize,
+    pub source_set: Vec<usize>,
+    pub letter: usize,
+    pub target: usize,
+    pub target_set: Vec<usize>,
+    /// Whether the target subset was first discovered in this step.
+    pub discovered: bool,
+}
+
+/// Statistics of a sequential superset construction, to estimate its memory use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeterminizeStats {
+    /// Largest number of subsets waiting in the frontier at once.
+    pub peak_frontier: usize,
+    /// Number of subsets mapped to DFA states - The peak size of the subset map.
+    pub subsets: usize,
+    /// Compressed bytes of the subsets in the subset map.
+    pub subset_bytes: usize,
+}
+
+/// Outcome of running a word through an automaton with an error state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    Accepted,
+    Rejected,
+    /// The error state was entered by reading the letter at this position of the word.
+    Error(usize),
+}
+
+/// Result of a determinization that can be cancelled.
+#[derive(Debug, Clone)]
+pub enum ResumableDeterminization {
+    Complete(Automaton),
+    /// The partial DFA holds every state discovered before cancelling, with the transitions of
+    /// the explored ones. The frontier holds each unexplored state with its set of NFA states.
+    Cancelled {
+        partial: Automaton,
+        frontier: Vec<(usize, Vec<usize>)>,
+    },
+}
+
+/// Durations of one iteration of the determinization then minimization pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PipelineTiming {
+    pub determinize: Duration,
+    pub minimize: Duration,
+}
+
+impl PipelineTiming {
+    /// Header of the CSV rows written by `to_csv_row`.
+    pub const CSV_HEADER: &'static str = "determinize_ns,minimize_ns";
+
+    /// Get the timing as a CSV row of nanoseconds.
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{}",
+            self.determinize.as_nanos(),
+            self.minimize.as_nanos()
+        )
+    }
 }
 
 impl Automaton {
@@ -50,6 +154,10 @@ impl Automaton {
             table,
             start,
             end,
+            accept_priority: None,
+            probabilities: None,
+            epsilon_table: None,
+            alphabet_labels: None,
         }
     }
 
@@ -58,68 +166,560 @@ impl Automaton {
         Automaton::new(AutomatonType::Det, 0, 0, vec![], vec![], vec![])
     }
 
+    /// Return a complete deterministic automaton of `size` states that all accept and loop on
+    /// every letter - It recognizes every word.
+    pub fn accept_all(size: usize, alphabet: usize) -> Automaton {
+        let mut ret = Automaton::accept_none(size, alphabet);
+        ret.end = (0..size).collect();
+        ret
+    }
+
+    /// Return a complete deterministic automaton of `size` states that loop on every letter
+    /// without any accept state - It recognizes the empty language.
+    pub fn accept_none(size: usize, alphabet: usize) -> Automaton {
+        Automaton::new(
+            AutomatonType::Det,
+            size,
+            alphabet,
+            (0..size)
+                .flat_map(|s| (1..alphabet + 1).map(move |a| (s, a, s)))
+                .collect(),
+            if size > 0 { vec![0] } else { vec![] },
+            vec![],
+        )
+    }
+
+    /// Return a complete deterministic automaton from a `size × alphabet` matrix of target states,
+    /// where `matrix[s][a - 1]` is the state reached from `s` by reading letter `a`.
+    /// Panics if a row does not cover the whole alphabet or a state is out of range.
+    pub fn from_matrix(
+        matrix: &[Vec<usize>],
+        alphabet: usize,
+        start: usize,
+        end: Vec<usize>,
+    ) -> Automaton {
+        let size = matrix.len();
+        assert!(start < size, "Start state {} is out of range!", start);
+        assert!(
+            end.iter().all(|s| *s < size),
+            "Accept states {:?} are out of range!",
+            end
+        );
+        let mut table = Vec::with_capacity(size * alphabet);
+        for (s, row) in matrix.iter().enumerate() {
+            assert_eq!(
+                row.len(),
+                alphabet,
+                "Row {} does not match the alphabet!",
+                s
+            );
+            for (a, e) in row.iter().enumerate() {
+                assert!(*e < size, "Target {} of row {} is out of range!", e, s);
+                table.push((s, a + 1, *e));
+            }
+        }
+        Automaton::new(AutomatonType::Det, size, alphabet, table, vec![start], end)
+    }
+
+    /// Return the `size × alphabet` matrix of target states of a complete deterministic automaton,
+    /// or None if the automaton is not deterministic or some transition is missing.
+    pub fn transition_matrix(&self) -> Option<Vec<Vec<usize>>> {
+        if self.automaton_type != AutomatonType::Det {
+            return None;
+        }
+        let mut matrix: Vec<Vec<Option<usize>>> = vec![vec![None; self.alphabet]; self.size];
+        for (s, a, e) in &self.table {
+            if *a == 0 || *a > self.alphabet || *s >= self.size {
+                return None;
+            }
+            match matrix[*s][a - 1] {
+                Some(t) if t != *e => return None,
+                _ => matrix[*s][a - 1] = Some(*e),
+            }
+        }
+        matrix
+            .into_iter()
+            .map(|row| row.into_iter().collect::<Option<Vec<usize>>>())
+            .collect()
+    }
+
+    /// Return sorted copies of the transitions, start states and accept states of the automaton,
+    /// to compare automata independently of the order they were built in.
+    pub fn to_transition_triples_sorted(&self) -> SortedTransitions {
+        let mut table = self.table.clone();
+        let mut start = self.start.clone();
+        let mut end = self.end.clone();
+        table.sort();
+        start.sort();
+        end.sort();
+        (table, start, end)
+    }
+
     /// Return a determinized version of the given automata - Using Rabin-Scott's Superset Construction algorithm.
     pub fn determinized(&self, kind: AlgorithmKind) -> Automaton {
+        self.determinized_with::<Ubig>(kind)
+    }
+
+    /// Return a determinized version of the automaton, representing the sets of states of the
+    /// superset construction with `S` - See `RoaringStateSet` for NFAs with sparse sets.
+    pub fn determinized_with<S: StateSet>(&self, kind: AlgorithmKind) -> Automaton {
         // Return same automaton as it already is deterministic.
         let ret = match self.automaton_type {
             AutomatonType::Det => self.clone(),
+            AutomatonType::NonDet if self.is_deterministic() => self.determinized_fast_path(),
             AutomatonType::NonDet => {
-                let (transitions, a_size, a_start, a_end) = match kind {
-                    AlgorithmKind::Sequential => rabin_scott_seq(&self),
-                    AlgorithmKind::Multithreaded(n_threads) => rabin_scott_mt(&self, n_threads),
-                };
-                return Automaton {
-                    automaton_type: AutomatonType::Det,
-                    size: a_size,
-                    alphabet: self.alphabet,
-                    table: transitions,
-                    start: a_start,
-                    end: a_end,
-                };
+                return self.superset_dfa(match kind {
+                    AlgorithmKind::Sequential => rabin_scott_seq::<S>(self),
+                    AlgorithmKind::Multithreaded(n_threads) => rabin_scott_mt::<S>(self, n_threads),
+                    AlgorithmKind::Rayon => rabin_scott_rayon::<S>(self),
+                });
             }
         };
         return ret;
     }
 
+    /// Get the DFA built by a superset construction of the automaton, over the same alphabet and
+    /// letter names.
+    fn superset_dfa(&self, determinization: Determinization) -> Automaton {
+        let (transitions, a_size, a_start, a_end) = determinization;
+        let mut ret = Automaton::new(
+            AutomatonType::Det,
+            a_size,
+            self.alphabet,
+            transitions,
+            a_start,
+            a_end,
+        );
+        ret.alphabet_labels = self.alphabet_labels.clone();
+        ret
+    }
+
+    /// Return a determinized version of the automaton, choosing between sequential and
+    /// multithreaded determinization with `auto_algorithm_kind`.
+    pub fn determinized_auto(&self) -> Automaton {
+        self.determinized(self.auto_algorithm_kind())
+    }
+
+    /// Choose how to determinize the automaton from a bounded probe of its superset
+    /// construction - Multithreaded on every available core for large enough work estimates,
+    /// sequential otherwise.
+    pub fn auto_algorithm_kind(&self) -> AlgorithmKind {
+        if self.automaton_type == AutomatonType::Det
+            || self.powerset_size_estimate(AUTO_PROBE_LIMIT) * self.size < AUTO_MULTITHREADED_WORK
+        {
+            return AlgorithmKind::Sequential;
+        }
+        match thread::available_parallelism() {
+            Ok(n) if n.get() > 1 => AlgorithmKind::Multithreaded(n.get()),
+            _ => AlgorithmKind::Sequential,
+        }
+    }
+
+    /// Return a determinized version of the automaton, checking that the result is deterministic
+    /// and complete. Returns an error naming the offending state and letter otherwise.
+    pub fn determinized_checked(&self, kind: AlgorithmKind) -> Result<Automaton, String> {
+        let dfa = self.determinized(kind);
+        if dfa.is_deterministic() && dfa.is_complete() {
+            return Ok(dfa);
+        } else if dfa.start.len() != 1 {
+            return Err(format!("Determinization has start states {:?}!", dfa.start));
+        }
+        let mut targets: HashMap<(usize, usize), usize> = HashMap::new();
+        for (s, a, e) in &dfa.table {
+            if *a == 0 {
+                return Err(format!("State {} has an empty char transition!", s));
+            } else if *targets.entry((*s, *a)).or_insert(*e) != *e {
+                return Err(format!("State {} has several targets on letter {}!", s, a));
+            }
+        }
+        match dfa.find_missing_transition() {
+            Some((s, a)) => Err(format!("State {} has no transition on letter {}!", s, a)),
+            None => Ok(dfa),
+        }
+    }
+
+    /// Determinize a structurally deterministic automaton without the superset construction -
+    /// States are numbered in the order the superset construction would discover their
+    /// singletons, and missing transitions lead to a sink standing for the empty set.
+    fn determinized_fast_path(&self) -> Automaton {
+        let arr = self.get_transition_array();
+        let end: HashSet<usize> = self.end.iter().copied().collect();
+        let mut ids: HashMap<Option<usize>, usize> = HashMap::from([(Some(self.start[0]), 0)]);
+        let mut frontier: VecDeque<Option<usize>> = VecDeque::from([Some(self.start[0])]);
+        let mut ret = Automaton::new(
+            AutomatonType::Det,
+            0,
+            self.alphabet,
+            vec![],
+            vec![0],
+            vec![],
+        );
+        while let Some(next) = frontier.pop_front() {
+            if next.is_some_and(|s| end.contains(&s)) {
+                ret.end.push(ids[&next]);
+            }
+            for (a, letter_arr) in arr.iter().enumerate().skip(1) {
+                let target = next.and_then(|s| letter_arr[s].first().copied());
+                if !ids.contains_key(&target) {
+                    ids.insert(target, ids.len());
+                    frontier.push_back(target);
+                }
+                ret.table.push((ids[&next], a, ids[&target]));
+            }
+        }
+        ret.size = ids.len();
+        ret.alphabet_labels = self.alphabet_labels.clone();
+        ret
+    }
+
+    /// Return a determinized version of the automaton using the sequential superset construction,
+    /// keeping at most `frontier_limit` unexplored subsets in memory and spilling the others to a
+    /// temporary file. Fails if the temporary file cannot be written or read.
+    pub fn determinized_spilling(&self, frontier_limit: usize) -> io::Result<Automaton> {
+        if self.automaton_type == AutomatonType::Det {
+            return Ok(self.clone());
+        }
+        let determinization = rabin_scott_seq_with(self, &mut SpillQueue::new(frontier_limit)?)?;
+        Ok(self.superset_dfa(determinization))
+    }
+
+    /// Return a sequential determinization of the automaton, stopping before exploring the next
+    /// subset once `cancel` is set and returning the partial DFA built so far.
+    pub fn determinized_resumable(&self, cancel: &AtomicBool) -> ResumableDeterminization {
+        self.determinized_resumable_with(VecDeque::new(), cancel)
+    }
+
+    /// Return a sequential determinization of the automaton, numbering the subsets of states with
+    /// a trie keyed on their sorted states instead of a map of compressed subsets. Gives the same
+    /// DFA as the sequential `determinized`.
+    pub fn determinized_trie(&self) -> Automaton {
+        if self.automaton_type == AutomatonType::Det || self.is_deterministic() {
+            return self.determinized(AlgorithmKind::Sequential);
+        }
+        self.superset_dfa(rabin_scott_seq_trie(self))
+    }
+
+    /// Return a sequential determinization of the automaton exploring subsets from the given
+    /// in-memory frontier, stopping once `cancel` is set - See `determinized_resumable`.
+    pub(crate) fn determinized_resumable_with<F: Frontier>(
+        &self,
+        frontier: F,
+        cancel: &AtomicBool,
+    ) -> ResumableDeterminization {
+        if self.automaton_type == AutomatonType::Det {
+            return ResumableDeterminization::Complete(self.clone());
+        }
+        let mut frontier = CancellableFrontier::new(frontier, cancel);
+        // An in-memory frontier cannot fail.
+        let dfa = self.superset_dfa(rabin_scott_seq_with(self, &mut frontier).unwrap());
+        let a_size = dfa.size;
+        // Subsets are numbered as they are pushed, so the unexplored ones are the last states.
+        let mut remaining = vec![];
+        while let Some(set) = frontier.inner.pop_front().unwrap() {
+            remaining.push(set.get_seq());
+        }
+        match remaining.is_empty() {
+            true => ResumableDeterminization::Complete(dfa),
+            false => ResumableDeterminization::Cancelled {
+                frontier: (a_size - remaining.len()..a_size).zip(remaining).collect(),
+                partial: dfa,
+            },
+        }
+    }
+
+    /// Return a sequential determinization of the automaton, along with the statistics of its
+    /// superset construction.
+    pub fn determinized_with_stats(&self) -> (Automaton, DeterminizeStats) {
+        if self.automaton_type == AutomatonType::Det {
+            return (self.clone(), DeterminizeStats::default());
+        }
+        let mut frontier = StatsFrontier::default();
+        // An in-memory frontier cannot fail.
+        let dfa = self.superset_dfa(rabin_scott_seq_with(self, &mut frontier).unwrap());
+        (dfa, frontier.stats)
+    }
+
+    /// Return the minimal DFA of the automaton, merging the equivalent DFA states discovered so
+    /// far every few explored states to drop the transition rows of the merged states.
+    /// Experimental: only states with the same acceptance and the same targets are merged on the
+    /// fly, and every discovered subset is still kept to number the states, so peak memory is not
+    /// capped.
+    pub fn determinize_minimize_fused(&self) -> Automaton {
+        if self.automaton_type == AutomatonType::Det {
+            return self.minimized();
+        }
+        self.superset_dfa(rabin_scott_fused(self, FUSED_MERGE_INTERVAL))
+            .minimized()
+    }
+
+    /// Return the steps of the superset construction of the automaton, in the order the
+    /// sequential determinization explores them.
+    pub fn determinize_trace(&self) -> Vec<DeterminizeStep> {
+        rabin_scott_trace(self)
+    }
+
+    /// Return a determinized version of the automaton where each accept state keeps the lowest
+    /// accept priority among the states of its subset. Automata without accept priorities give
+    /// every accept state priority 0.
+    pub fn determinized_with_priority(&self) -> Automaton {
+        let priority: HashMap<usize, usize> = match &self.accept_priority {
+            Some(p) => self.end.iter().copied().zip(p.iter().copied()).collect(),
+            None => self.end.iter().map(|s| (*s, 0)).collect(),
+        };
+        let steps = self.determinize_trace();
+
+        // Subsets are discovered in the order of their ids.
+        let mut subsets = vec![self.get_start_set(&self.get_transition_array()).get_seq()];
+        steps
+            .iter()
+            .filter(|step| step.discovered)
+            .for_each(|step| subsets.push(step.target_set.clone()));
+        let (end, accept_priority): (Vec<usize>, Vec<usize>) = subsets
+            .iter()
+            .enumerate()
+            .filter_map(|(i, set)| {
+                set.iter()
+                    .filter_map(|s| priority.get(s))
+                    .min()
+                    .map(|p| (i, *p))
+            })
+            .unzip();
+
+        let mut ret = Automaton::new(
+            AutomatonType::Det,
+            subsets.len(),
+            self.alphabet,
+            steps
+                .iter()
+                .map(|step| (step.source, step.letter, step.target))
+                .collect(),
+            vec![0],
+            end,
+        );
+        ret.accept_priority = Some(accept_priority);
+        ret.alphabet_labels = self.alphabet_labels.clone();
+        ret
+    }
+
     /// Return a minimized version of the given automata - Using Hopcroft's partition algorithm.
+    /// Transitions, start and accept states of the result are sorted. A DFA missing transitions
+    /// is completed with a sink state first, so the result is complete.
     pub fn minimized(&self) -> Automaton {
         if let AutomatonType::NonDet = self.automaton_type {
             return self.clone();
-        } else if self.size <= 2 {
-            return self.clone();
         }
 
-        let tuple = hopcroft_algo(&self);
-        let p = tuple.0;
-        let len = tuple.1;
+        // Hopcroft's algorithm needs every state to have a transition on every letter.
+        if self.find_missing_transition().is_some() {
+            return self.completed().into_minimized();
+        }
+        let (p, len) = hopcroft_algo(self);
+        self.quotient(&p, len)
+    }
 
-        let ret = Automaton {
+    /// Return a minimized version of the automaton, reusing its transition table so that the
+    /// automaton and its minimization are not held in memory together.
+    pub fn into_minimized(self) -> Automaton {
+        if let AutomatonType::NonDet = self.automaton_type {
+            return self;
+        }
+
+        if self.find_missing_transition().is_some() {
+            return self.completed().into_minimized();
+        }
+        let (p, len) = hopcroft_algo(&self);
+        self.into_quotient(&p, len)
+    }
+
+    /// Return a minimized version of the automaton without its unreachable states, along with
+    /// the sorted ids of the unreachable states dropped. Plain minimization keeps unreachable
+    /// states that are not equivalent to reachable ones.
+    pub fn minimized_with_dropped(&self) -> (Automaton, Vec<usize>) {
+        if let AutomatonType::NonDet = self.automaton_type {
+            return (self.clone(), vec![]);
+        }
+        let mut reachable = self.get_successor_states(&self.start);
+        reachable.extend(self.start.iter().copied());
+        let dropped: Vec<usize> = (0..self.size).filter(|s| !reachable.contains(s)).collect();
+        let trimmed = self.without_states(&dropped.iter().copied().collect());
+        (trimmed.minimized(), dropped)
+    }
+
+    /// Return the automaton with the states of each partition merged, from a map of states to
+    /// their partition and the number of partitions.
+    pub(crate) fn quotient(&self, p: &HashMap<usize, usize>, len: usize) -> Automaton {
+        self.clone().into_quotient(p, len)
+    }
+
+    /// Return the automaton with the states of each partition merged - See `quotient`. The
+    /// transition table is rewritten in place.
+    pub(crate) fn into_quotient(mut self, p: &HashMap<usize, usize>, len: usize) -> Automaton {
+        self.table.iter_mut().for_each(|t| {
+            if let (Some(t0), Some(t2)) = (p.get(&t.0), p.get(&t.2)) {
+                *t = (*t0, t.1, *t2);
+            } else {
+                panic!();
+            }
+        });
+        // Sort before removing duplicates, so the table comes out in the same order every run.
+        self.table.sort_unstable();
+        self.table.dedup();
+
+        Automaton {
             automaton_type: AutomatonType::Det,
             size: len,
             alphabet: self.alphabet,
-            table: self
-                .table
-                .clone()
-                .into_iter()
-                .map(|t| {
-                    if let (Some(t0), Some(t2)) = (p.get(&t.0), p.get(&t.2)) {
-                        (*t0, t.1, *t2)
-                    } else {
-                        panic!();
-                    }
-                })
-                .collect::<HashSet<(usize, usize, usize)>>()
-                .into_iter()
-                .collect::<Vec<(usize, usize, usize)>>(),
-            start: Automaton::get_part_vec_from_vec(&p, &self.start),
-            end: Automaton::get_part_vec_from_vec(&p, &self.end),
-        };
-        return ret;
+            start: Automaton::get_part_vec_from_vec(p, &self.start),
+            end: Automaton::get_part_vec_from_vec(p, &self.end),
+            table: self.table,
+            accept_priority: None,
+            probabilities: None,
+            epsilon_table: None,
+            alphabet_labels: self.alphabet_labels,
+        }
+    }
+
+    /// Return a minimized version of the automaton, checking that it recognizes the same words
+    /// as the automaton up to `max_len` letters. Returns an error with a word on which they
+    /// disagree otherwise.
+    pub fn minimized_checked(&self, max_len: usize) -> Result<Automaton, String> {
+        let min = self.minimized();
+        self.check_same_words(&min, max_len)?;
+        Ok(min)
+    }
+
+    /// Check that a minimization recognizes the same words as the automaton up to `max_len`
+    /// letters.
+    pub(crate) fn check_same_words(&self, min: &Automaton, max_len: usize) -> Result<(), String> {
+        match self.distinguishing_word_within(min, max_len) {
+            Some(word) => Err(format!(
+                "Minimization disagrees with the automaton on word {:?}!",
+                word
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Return the determinization of the reverse of the automaton - The intermediate automaton
+    /// of Brzozowski's minimization, which can be cached and reused.
+    pub fn reverse_dfa(&self, kind: AlgorithmKind) -> Automaton {
+        self.clone().reverse_transitions().determinized(kind)
+    }
+
+    /// Return a minimized version of the given automata - Using Brzozowski's double reversal
+    /// algorithm.
+    pub fn brzozowski_minimized(&self, kind: AlgorithmKind) -> Automaton {
+        self.reverse_dfa(kind).reverse_dfa(kind)
+    }
+
+    /// Return the number of states of the minimized determinization of the automaton, without
+    /// building the minimized transition table. A partial DFA is completed with a sink state
+    /// first, as in `minimized`.
+    pub fn minimal_state_count(&self, kind: AlgorithmKind) -> usize {
+        let dfa = self.determinized(kind);
+        match dfa.find_missing_transition() {
+            Some(_) => hopcroft_algo(&dfa.completed()).1,
+            None => hopcroft_algo(&dfa).1,
+        }
+    }
+
+    /// Return the partitions of the states of a DFA after each splitter processed by Hopcroft's
+    /// algorithm, without the empty ones - The last snapshot holds the classes of equivalent
+    /// states.
+    pub fn letter_partition_refinement_trace(&self) -> Vec<Vec<Vec<usize>>> {
+        let mut trace = vec![];
+        hopcroft_algo_with(self, |p| {
+            trace.push(p.iter().filter(|c| !c.is_empty()).cloned().collect())
+        });
+        trace
+    }
+
+    /// Toggle whether a state is accepting, then replace the automaton with its minimization.
+    /// State ids are renumbered by the minimization.
+    pub fn toggle_accept_and_remap(&mut self, state: usize) {
+        match self.end.iter().position(|s| *s == state) {
+            Some(i) => {
+                self.end.remove(i);
+            }
+            None => self.end.push(state),
+        }
+        *self = self.reminimize_after_accept_change(&[state]);
+    }
+
+    /// Return a minimized version of an automaton whose accept states were changed.
+    /// Hopcroft's algorithm refines the partition of the whole automaton from the accept split,
+    /// so a change of acceptance can merge or split classes anywhere - This reruns the full
+    /// minimization for now, and `changed` is kept so an incremental version can use it.
+    pub fn reminimize_after_accept_change(&self, _changed: &[usize]) -> Automaton {
+        self.minimized()
+    }
+
+    /// Return a minimized version of the given automata without its dead sink state.
+    /// The result is a partial DFA recognizing the same language.
+    pub fn minimized_partial(&self) -> Automaton {
+        self.minimized().without_sink()
+    }
+
+    /// Return the minimized determinization of the automaton, calling `log` with the name and
+    /// state count of each stage - After determinization, minimization and removal of the sink
+    /// of the minimized automaton.
+    pub fn pipeline_with_log(
+        &self,
+        kind: AlgorithmKind,
+        mut log: impl FnMut(&str, usize),
+    ) -> Automaton {
+        let dfa = self.determinized(kind);
+        log("determinized", dfa.size);
+        let minimal = dfa.minimized();
+        log("minimized", minimal.size);
+        // A minimal DFA has at most one dead state, which is its sink unless it is the start.
+        let sink = minimal.dead_state().filter(|s| !minimal.start.contains(s));
+        log("trimmed", minimal.size - sink.is_some() as usize);
+        minimal
+    }
+
+    /// Determinize then minimize the automaton, and write the result to `writer` in the format
+    /// read by `Automaton::from`. The determinization is consumed by the minimization, so it is
+    /// never held in memory along with the minimized automaton.
+    pub fn pipeline_to_writer<W: Write>(
+        &self,
+        kind: AlgorithmKind,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        let minimal = self.determinized(kind).into_minimized();
+        writeln!(writer, "{}", minimal)?;
+        writer.flush()
+    }
+
+    /// Time determinization then minimization of the automaton over a number of iterations.
+    pub fn benchmark_pipeline(
+        &self,
+        kind: AlgorithmKind,
+        iterations: usize,
+    ) -> Vec<PipelineTiming> {
+        (0..iterations)
+            .map(|_| {
+                let start = Instant::now();
+                let dfa = self.determinized(kind);
+                let determinize = start.elapsed();
+                let start = Instant::now();
+                dfa.minimized();
+                PipelineTiming {
+                    determinize,
+                    minimize: start.elapsed(),
+                }
+            })
+            .collect()
     }
 
     /// Reverse all transitions of the automaton
     pub fn reverse_transitions(mut self) -> Self {
         self.table = self.table.drain(..).map(|(s, a, e)| (e, a, s)).collect();
+        if let Some(epsilon_table) = &mut self.epsilon_table {
+            epsilon_table
+                .iter_mut()
+                .for_each(|(s, e)| (*s, *e) = (*e, *s));
+        }
         (self.start, self.end) = (self.end, self.start);
         self.automaton_type = AutomatonType::NonDet;
         self
@@ -130,7 +730,7 @@ impl Automaton {
     ///////////////
 
     /// Add a state into a set of states, adding states connected via the empty char to the set with it.
-    pub fn add_state(&self, arr: &Vec<Vec<Vec<usize>>>, num: &mut Ubig, bit: usize) {
+    pub fn add_state<S: StateSet>(&self, arr: &[Vec<Vec<usize>>], num: &mut S, bit: usize) {
         let mut queue: VecDeque<usize> = VecDeque::from([bit]);
         while let Some(b) = queue.pop_front() {
             if !num.bit_at(&b) {
@@ -143,6 +743,36 @@ impl Automaton {
         }
     }
 
+    /// Get the set of start states, closed over empty char transitions.
+    pub(crate) fn get_start_set(&self, arr: &[Vec<Vec<usize>>]) -> Ubig {
+        let mut set = Ubig::new();
+        self.start
+            .iter()
+            .for_each(|s| self.add_state(arr, &mut set, *s));
+        // Closing over empty char transitions only ever grows the set of start states.
+        debug_assert!({
+            let mut start = Ubig::new();
+            self.start.iter().for_each(|s| start.set_to(s, true));
+            start.is_subset_of(&set)
+        });
+        set
+    }
+
+    /// Get the set of states reached from a set of states by reading a letter, closed over
+    /// empty char transitions. Letters outside of the alphabet lead to the empty set.
+    pub(crate) fn get_next_set(&self, arr: &[Vec<Vec<usize>>], set: &Ubig, a: usize) -> Ubig {
+        let mut next = Ubig::new();
+        if a == 0 || a > self.alphabet {
+            return next;
+        }
+        set.get_seq().into_iter().for_each(|s| {
+            arr[a][s].iter().for_each(|t| {
+                self.add_state(arr, &mut next, *t);
+            })
+        });
+        next
+    }
+
     fn get_empty_transition_arr(&self) -> Vec<Vec<Vec<usize>>> {
         (0..self.alphabet + 1)
             .map(|_| (0..self.size + 1).map(|_| Vec::new()).collect())
@@ -155,6 +785,10 @@ impl Automaton {
         (&self.table)
             .into_iter()
             .for_each(|t| arr[t.1][t.0].push(t.2));
+        self.epsilon_table
+            .iter()
+            .flatten()
+            .for_each(|(s, e)| arr[0][*s].push(*e));
         return arr;
     }
 
@@ -164,9 +798,26 @@ impl Automaton {
         (&self.table)
             .into_iter()
             .for_each(|t| arr[t.1][t.2].push(t.0));
+        self.epsilon_table
+            .iter()
+            .flatten()
+            .for_each(|(s, e)| arr[0][*e].push(*s));
         return arr;
     }
 
+    /// Get the transitions of the table, followed by the empty char transitions kept apart from
+    /// it as letter 0 transitions.
+    pub(crate) fn transitions_with_epsilon(
+        &self,
+    ) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.table.iter().copied().chain(
+            self.epsilon_table
+                .iter()
+                .flatten()
+                .map(|(s, e)| (*s, 0, *e)),
+        )
+    }
+
     ////////////////////
     // Static methods //
     ////////////////////
@@ -176,7 +827,7 @@ impl Automaton {
         s.clone()
             .into_iter()
             .map(|s| *p.get(&s).unwrap())
-            .collect::<HashSet<usize>>()
+            .collect::<BTreeSet<usize>>()
             .into_iter()
             .collect::<Vec<usize>>()
     }
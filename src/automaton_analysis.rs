@@ -0,0 +1,861 @@
+use std::collections::{hash_map::Entry, BTreeSet, HashMap, HashSet, VecDeque};
+
+use crate::{
+    automaton::{AlgorithmKind, Automaton, AutomatonType, RunOutcome},
+    rng::SplitMix64,
+    ubig::Ubig,
+};
+
+impl Automaton {
+    /// Check whether the automaton accepts a word - Walks the transitions of a deterministic
+    /// automaton from its start state, and otherwise simulates the set of states reachable by
+    /// reading the word, closed over empty char transitions.
+    /// Letters outside of the alphabet, and missing transitions of a DFA, are rejected.
+    pub fn accepts(&self, word: &[usize]) -> bool {
+        let arr = self.get_transition_array();
+        match self.automaton_type {
+            AutomatonType::Det if self.start.len() == 1 && !self.has_epsilon() => {
+                let mut state = self.start[0];
+                for a in word {
+                    match arr
+                        .get(*a)
+                        .filter(|_| *a != 0)
+                        .and_then(|l| l[state].first())
+                    {
+                        Some(next) => state = *next,
+                        None => return false,
+                    }
+                }
+                self.end.contains(&state)
+            }
+            _ => self.accepts_with(&arr, word),
+        }
+    }
+
+    /// Run a word through the automaton, reporting the position of the letter that led into the
+    /// error state if only the error state remains reachable.
+    pub fn run(&self, word: &[usize], error_state: usize) -> RunOutcome {
+        let arr = self.get_transition_array();
+        let mut set = self.get_start_set(&arr);
+        for (i, a) in word.iter().enumerate() {
+            set = self.get_next_set(&arr, &set, *a);
+            if set.get_seq() == [error_state] {
+                return RunOutcome::Error(i);
+            }
+        }
+        if self.end.iter().any(|s| set.bit_at(s)) {
+            RunOutcome::Accepted
+        } else {
+            RunOutcome::Rejected
+        }
+    }
+
+    /// Get the length of the longest prefix of a word accepted by the automaton - Simulates the
+    /// set of reachable states, stopping once it is empty. Returns None if no prefix, the empty
+    /// one included, is accepted.
+    pub fn longest_accepted_prefix(&self, word: &[usize]) -> Option<usize> {
+        let arr = self.get_transition_array();
+        let mut set = self.get_start_set(&arr);
+        let accepting = |set: &Ubig| self.end.iter().any(|s| set.bit_at(s));
+        let mut longest = accepting(&set).then_some(0);
+        for (i, a) in word.iter().enumerate() {
+            set = self.get_next_set(&arr, &set, *a);
+            if set.get_seq().is_empty() {
+                break;
+            } else if accepting(&set) {
+                longest = Some(i + 1);
+            }
+        }
+        longest
+    }
+
+    /// Estimate the fraction of words of a given length accepted by the automaton, by sampling
+    /// uniformly random words from a seeded generator. Returns 0 if no word can be sampled.
+    pub fn estimate_accept_ratio(&self, length: usize, samples: usize, seed: u64) -> f64 {
+        if samples == 0 || (self.alphabet == 0 && length > 0) {
+            return 0.0;
+        }
+        let transition_arr = self.get_transition_array();
+        let mut rng = SplitMix64::new(seed);
+        let mut word = vec![0; length];
+        let accepted = (0..samples)
+            .filter(|_| {
+                word.iter_mut()
+                    .for_each(|a| *a = rng.below(self.alphabet) + 1);
+                self.accepts_with(&transition_arr, &word)
+            })
+            .count();
+        accepted as f64 / samples as f64
+    }
+
+    /// Estimate the number of states of the determinization of the automaton, exploring the
+    /// superset construction breadth first until `probe_limit` subsets are found. Returns the
+    /// exact number of subsets if it is below the limit, and the limit otherwise.
+    pub fn powerset_size_estimate(&self, probe_limit: usize) -> usize {
+        let arr = self.get_transition_array();
+        let start = self.get_start_set(&arr);
+        let mut seen: HashSet<Vec<usize>> = HashSet::from([start.get_seq()]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(set) = queue.pop_front() {
+            for a in 1..self.alphabet + 1 {
+                if seen.len() >= probe_limit {
+                    return probe_limit;
+                }
+                let next = self.get_next_set(&arr, &set, a);
+                if seen.insert(next.get_seq()) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        seen.len()
+    }
+
+    /// Check whether two automata recognize the same language - Determinizes both automata and
+    /// searches their product for a pair of states that disagree on acceptance.
+    /// Automata over alphabets of different sizes are never equivalent.
+    pub fn equivalent(&self, other: &Automaton) -> bool {
+        self.alphabet == other.alphabet && self.distinguishing_witness(other).is_none()
+    }
+
+    /// Check whether two automata are bisimilar - Refines the states of both automata by
+    /// acceptance and by the classes reached on each letter, the empty char included, until
+    /// stable. Both automata are bisimilar if their start states fall into the same classes.
+    /// Bisimilar automata recognize the same language, but the converse does not hold.
+    pub fn is_bisimilar_to(&self, other: &Automaton) -> bool {
+        if self.alphabet != other.alphabet {
+            return false;
+        }
+        // Successors of the states of both automata, those of `other` numbered after `self`.
+        let mut successors: Vec<Vec<(usize, usize)>> = vec![];
+        let mut accepting: Vec<bool> = vec![];
+        for (aut, offset) in [(self, 0), (other, self.size)] {
+            let arr = aut.get_transition_array();
+            let end: HashSet<usize> = aut.end.iter().copied().collect();
+            for s in 0..aut.size {
+                successors.push(
+                    arr.iter()
+                        .enumerate()
+                        .flat_map(|(a, letter_arr)| {
+                            letter_arr[s].iter().map(move |e| (a, e + offset))
+                        })
+                        .collect(),
+                );
+                accepting.push(end.contains(&s));
+            }
+        }
+
+        let mut classes: Vec<usize> = accepting.iter().map(|a| *a as usize).collect();
+        let mut class_count = 0;
+        loop {
+            let mut signatures: HashMap<(usize, BTreeSet<(usize, usize)>), usize> = HashMap::new();
+            let refined: Vec<usize> = (0..classes.len())
+                .map(|s| {
+                    let reached = successors[s]
+                        .iter()
+                        .map(|(a, e)| (*a, classes[*e]))
+                        .collect();
+                    let next_id = signatures.len();
+                    *signatures.entry((classes[s], reached)).or_insert(next_id)
+                })
+                .collect();
+            classes = refined;
+            if signatures.len() == class_count {
+                break;
+            }
+            class_count = signatures.len();
+        }
+
+        let start_classes = |aut: &Automaton, offset: usize| -> HashSet<usize> {
+            aut.start.iter().map(|s| classes[s + offset]).collect()
+        };
+        start_classes(self, 0) == start_classes(other, self.size)
+    }
+
+    /// Get a shortest word accepted by exactly one of two automata, along with whether it is
+    /// accepted by this automaton (true) or by the other one (false). Returns None if both
+    /// automata recognize the same language over the larger of their alphabets.
+    pub fn distinguishing_witness(&self, other: &Automaton) -> Option<(Vec<usize>, bool)> {
+        let (a, b) = (
+            self.determinized(AlgorithmKind::Sequential),
+            other.determinized(AlgorithmKind::Sequential),
+        );
+        let (arr_a, arr_b) = (a.get_transition_array(), b.get_transition_array());
+        let end_a: HashSet<usize> = a.end.iter().copied().collect();
+        let end_b: HashSet<usize> = b.end.iter().copied().collect();
+        let step = |arr: &Vec<Vec<Vec<usize>>>, s: Option<usize>, c: usize| {
+            s.and_then(|s| arr.get(c).and_then(|t| t[s].first().copied()))
+        };
+
+        // Missing states stand for the implicit sink of an incomplete DFA. Each visited pair
+        // keeps the pair and letter it was first reached from, to rebuild the word.
+        type Pair = (Option<usize>, Option<usize>);
+        let start = (a.start.first().copied(), b.start.first().copied());
+        let mut parents: HashMap<Pair, Option<(Pair, usize)>> = HashMap::from([(start, None)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some((p, q)) = queue.pop_front() {
+            let accept_p = p.is_some_and(|p| end_a.contains(&p));
+            let accept_q = q.is_some_and(|q| end_b.contains(&q));
+            if accept_p != accept_q {
+                let mut word = Vec::new();
+                let mut pair = (p, q);
+                while let Some(Some((parent, c))) = parents.get(&pair) {
+                    word.push(*c);
+                    pair = *parent;
+                }
+                word.reverse();
+                return Some((word, accept_p));
+            }
+            for c in 1..a.alphabet.max(b.alphabet) + 1 {
+                let next = (step(&arr_a, p, c), step(&arr_b, q, c));
+                if let Entry::Vacant(entry) = parents.entry(next) {
+                    entry.insert(Some(((p, q), c)));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Get a shortest word of at most `max_len` letters accepted by exactly one of two automata,
+    /// searching their determinizations breadth first.
+    pub(crate) fn distinguishing_word_within(
+        &self,
+        other: &Automaton,
+        max_len: usize,
+    ) -> Option<Vec<usize>> {
+        let (a, b) = (
+            self.determinized(AlgorithmKind::Sequential),
+            other.determinized(AlgorithmKind::Sequential),
+        );
+        let (arr_a, arr_b) = (a.get_transition_array(), b.get_transition_array());
+        let end_a: HashSet<usize> = a.end.iter().copied().collect();
+        let end_b: HashSet<usize> = b.end.iter().copied().collect();
+        let step = |arr: &Vec<Vec<Vec<usize>>>, s: Option<usize>, c: usize| {
+            s.and_then(|s| arr.get(c).and_then(|t| t[s].first().copied()))
+        };
+
+        let start = (a.start.first().copied(), b.start.first().copied());
+        let mut visited: HashSet<(Option<usize>, Option<usize>)> = HashSet::from([start]);
+        let mut queue = VecDeque::from([(start, Vec::new())]);
+        while let Some(((p, q), word)) = queue.pop_front() {
+            if p.is_some_and(|p| end_a.contains(&p)) != q.is_some_and(|q| end_b.contains(&q)) {
+                return Some(word);
+            }
+            if word.len() == max_len {
+                continue;
+            }
+            for c in 1..a.alphabet.max(b.alphabet) + 1 {
+                let next = (step(&arr_a, p, c), step(&arr_b, q, c));
+                if visited.insert(next) {
+                    let mut next_word = word.clone();
+                    next_word.push(c);
+                    queue.push_back((next, next_word));
+                }
+            }
+        }
+        None
+    }
+
+    /// Check whether no word is accepted by both automata - Searches the pairs of states of both
+    /// automata reachable from their start states, stopping at the first pair of accept states,
+    /// without building their product.
+    pub fn intersection_is_empty(&self, other: &Automaton) -> bool {
+        let (arr_a, arr_b) = (self.get_transition_array(), other.get_transition_array());
+        let end_a: HashSet<usize> = self.end.iter().copied().collect();
+        let end_b: HashSet<usize> = other.end.iter().copied().collect();
+
+        let mut visited: HashSet<(usize, usize)> = HashSet::new();
+        let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+        let mut visit = |pair: (usize, usize), queue: &mut VecDeque<(usize, usize)>| {
+            if visited.insert(pair) {
+                queue.push_back(pair);
+            }
+        };
+        for p in &self.start {
+            for q in &other.start {
+                visit((*p, *q), &mut queue);
+            }
+        }
+        while let Some((p, q)) = queue.pop_front() {
+            if end_a.contains(&p) && end_b.contains(&q) {
+                return false;
+            }
+            // Empty char transitions move one automaton at a time.
+            arr_a[0][p]
+                .iter()
+                .for_each(|p_next| visit((*p_next, q), &mut queue));
+            arr_b[0][q]
+                .iter()
+                .for_each(|q_next| visit((p, *q_next), &mut queue));
+            for c in 1..self.alphabet.min(other.alphabet) + 1 {
+                for p_next in &arr_a[c][p] {
+                    arr_b[c][q]
+                        .iter()
+                        .for_each(|q_next| visit((*p_next, *q_next), &mut queue));
+                }
+            }
+        }
+        true
+    }
+
+    /// Get a maximal set of pairwise distinguishable states reachable from the start states of a
+    /// deterministic automaton, chosen greedily in order - Its size is a lower bound on the number
+    /// of states of the minimal DFA, and equals it for a minimal DFA without unreachable states.
+    /// Missing transitions lead to an implicit sink.
+    pub fn fooling_set(&self) -> Vec<usize> {
+        let distinguishable = self.distinguishability_table();
+        let mut reachable = self.get_successor_states(&self.start);
+        reachable.extend(self.start.iter().copied());
+        let mut fooling: Vec<usize> = Vec::new();
+        for (s, row) in distinguishable.iter().enumerate().take(self.size) {
+            if reachable.contains(&s) && fooling.iter().all(|f| row[*f]) {
+                fooling.push(s);
+            }
+        }
+        fooling
+    }
+
+    /// Get the table of pairs of states of a deterministic automaton that some word
+    /// distinguishes, by filling it from the pairs disagreeing on acceptance.
+    fn distinguishability_table(&self) -> Vec<Vec<bool>> {
+        // The implicit sink of missing transitions is the last state.
+        let sink = self.size;
+        let arr = self.get_transition_array();
+        let next = |s: usize, c: usize| match s == sink {
+            true => sink,
+            false => arr[c][s].first().copied().unwrap_or(sink),
+        };
+        let end: HashSet<usize> = self.end.iter().copied().collect();
+        let mut table: Vec<Vec<bool>> = (0..sink + 1)
+            .map(|p| {
+                (0..sink + 1)
+                    .map(|q| end.contains(&p) != end.contains(&q))
+                    .collect()
+            })
+            .collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for p in 0..sink + 1 {
+                for q in 0..p {
+                    if !table[p][q] && (1..self.alphabet + 1).any(|c| table[next(p, c)][next(q, c)])
+                    {
+                        table[p][q] = true;
+                        table[q][p] = true;
+                        changed = true;
+                    }
+                }
+            }
+        }
+        table
+    }
+
+    /// Get the states that cannot read any letter, having no outgoing transitions other than
+    /// empty char ones, in increasing order. Combined with whether they accept, this tells dead
+    /// ends from final states.
+    pub fn trap_states(&self) -> Vec<usize> {
+        let reading: HashSet<usize> = self
+            .table
+            .iter()
+            .filter(|(_, a, _)| *a != 0)
+            .map(|(s, _, _)| *s)
+            .collect();
+        (0..self.size).filter(|s| !reading.contains(s)).collect()
+    }
+
+    /// Check whether the automaton is structurally deterministic, whatever its type - It has a
+    /// single start state, no empty char transition and at most one target per state and letter.
+    pub fn is_deterministic(&self) -> bool {
+        let mut targets: HashMap<(usize, usize), usize> = HashMap::new();
+        self.start.len() == 1
+            && !self.has_epsilon()
+            && self
+                .table
+                .iter()
+                .all(|(s, a, e)| *targets.entry((*s, *a)).or_insert(*e) == *e)
+    }
+
+    /// Check whether the automaton recognizes no word.
+    pub fn is_empty_language(&self) -> bool {
+        if self.end.is_empty() {
+            return true;
+        }
+        let live = self.get_live_states();
+        !self.start.iter().any(|s| live.contains(s))
+    }
+
+    /// Get a shortest word accepted by the automaton, or None if its language is empty - A
+    /// breadth first search over single states, where empty char transitions add no letter and
+    /// are explored before the others. A set of states reached by a word holds a state reached
+    /// by a shortest path reading that word, so no superset construction is needed.
+    pub fn shortest_word(&self) -> Option<Vec<usize>> {
+        let arr = self.get_transition_array();
+        let end: HashSet<usize> = self.end.iter().copied().collect();
+        // Each state keeps its distance, and the state and letter it was last reached from.
+        let mut dist: Vec<Option<usize>> = vec![None; self.size];
+        let mut parents: Vec<Option<(usize, usize)>> = vec![None; self.size];
+        let mut visited = vec![false; self.size];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        for s in &self.start {
+            dist[*s] = Some(0);
+            queue.push_back(*s);
+        }
+
+        while let Some(s) = queue.pop_front() {
+            if visited[s] {
+                continue;
+            }
+            visited[s] = true;
+            if end.contains(&s) {
+                let mut word = Vec::new();
+                let mut state = s;
+                while let Some((parent, a)) = parents[state] {
+                    if a != 0 {
+                        word.push(a);
+                    }
+                    state = parent;
+                }
+                word.reverse();
+                return Some(word);
+            }
+            let d = dist[s].unwrap();
+            for (a, letter_arr) in arr.iter().enumerate() {
+                let next_dist = if a == 0 { d } else { d + 1 };
+                for e in &letter_arr[s] {
+                    if dist[*e].is_none_or(|old| next_dist < old) {
+                        dist[*e] = Some(next_dist);
+                        parents[*e] = Some((s, a));
+                        if a == 0 {
+                            queue.push_front(*e);
+                        } else {
+                            queue.push_back(*e);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Check whether the automaton recognizes every word over its alphabet.
+    pub fn is_universal(&self) -> bool {
+        if self.end.is_empty() {
+            return false;
+        } else if self.size > 0
+            && self.end.len() >= self.size
+            && (0..self.size).all(|s| self.end.contains(&s))
+            && self.transition_matrix().is_some()
+        {
+            // Complete DFA where every state accepts.
+            return true;
+        }
+
+        let dfa = self.determinized(AlgorithmKind::Sequential).completed();
+        let arr = dfa.get_transition_array();
+        let end: HashSet<usize> = dfa.end.iter().copied().collect();
+        let mut reached: HashSet<usize> = dfa.start.iter().copied().collect();
+        let mut queue: VecDeque<usize> = dfa.start.iter().copied().collect();
+        while let Some(s) = queue.pop_front() {
+            if !end.contains(&s) {
+                return false;
+            }
+            for letter_arr in &arr {
+                for e in &letter_arr[s] {
+                    if reached.insert(*e) {
+                        queue.push_back(*e);
+                    }
+                }
+            }
+        }
+        !dfa.start.is_empty()
+    }
+
+    /// Check whether no accepted word is a proper prefix of another accepted word - Which holds
+    /// iff no accept state of the determinized automaton reachable from the start can reach an
+    /// accept state again.
+    pub fn is_prefix_free(&self) -> bool {
+        let dfa = self.determinized(AlgorithmKind::Sequential);
+        let mut reachable = dfa.get_successor_states(&dfa.start);
+        reachable.extend(dfa.start.iter().copied());
+        let end: Vec<usize> = dfa
+            .end
+            .iter()
+            .copied()
+            .filter(|e| reachable.contains(e))
+            .collect();
+        let after_end = dfa.get_successor_states(&end);
+        !dfa.end.iter().any(|e| after_end.contains(e))
+    }
+
+    /// Check whether every state has a transition on every letter of the alphabet.
+    pub fn is_complete(&self) -> bool {
+        self.find_missing_transition().is_none()
+    }
+
+    /// Get the first state and letter, in order, without any transition.
+    pub(crate) fn find_missing_transition(&self) -> Option<(usize, usize)> {
+        let defined: HashSet<(usize, usize)> =
+            self.table.iter().map(|(s, a, _)| (*s, *a)).collect();
+        (0..self.size)
+            .flat_map(|s| (1..self.alphabet + 1).map(move |a| (s, a)))
+            .find(|t| !defined.contains(t))
+    }
+
+    /// Get the dead state of the automaton - The unique state which does not accept and only has
+    /// transitions to itself on every letter, such as the sink added by completion or the empty
+    /// subset of a determinization. Returns None if there is no such state or several of them.
+    pub fn dead_state(&self) -> Option<usize> {
+        let end: HashSet<usize> = self.end.iter().copied().collect();
+        let mut self_loops: Vec<HashSet<usize>> = vec![HashSet::new(); self.size];
+        let mut leaves = vec![false; self.size];
+        self.transitions_with_epsilon().for_each(|(s, a, e)| {
+            if s == e && a != 0 {
+                self_loops[s].insert(a);
+            } else {
+                leaves[s] = true;
+            }
+        });
+        let mut dead = (0..self.size)
+            .filter(|s| !end.contains(s) && !leaves[*s] && self_loops[*s].len() == self.alphabet);
+        match (dead.next(), dead.next()) {
+            (Some(s), None) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Get the letters read along some path from a state to an accept state. Transitions to
+    /// states that cannot reach an accept state are left out, so dead states use no letter.
+    pub fn letters_reachable_from(&self, state: usize) -> HashSet<usize> {
+        let live = self.get_live_states();
+        let mut letters: HashSet<usize> = HashSet::new();
+        if !live.contains(&state) {
+            return letters;
+        }
+        let arr = self.get_transition_array();
+        let mut visited: HashSet<usize> = HashSet::from([state]);
+        let mut queue: VecDeque<usize> = VecDeque::from([state]);
+        while let Some(s) = queue.pop_front() {
+            for (a, letter_arr) in arr.iter().enumerate() {
+                for e in letter_arr[s].iter().filter(|e| live.contains(e)) {
+                    if a != 0 {
+                        letters.insert(a);
+                    }
+                    if visited.insert(*e) {
+                        queue.push_back(*e);
+                    }
+                }
+            }
+        }
+        letters
+    }
+
+    /// Check whether the automaton has any empty char transition.
+    pub fn has_epsilon(&self) -> bool {
+        self.table.iter().any(|(_, a, _)| *a == 0)
+            || self.epsilon_table.as_ref().is_some_and(|t| !t.is_empty())
+    }
+
+    /// Get the largest letter used by a transition of the table, empty char excluded.
+    /// A consistent automaton has an alphabet at least this large.
+    pub fn alphabet_size_from_table(&self) -> usize {
+        self.table.iter().map(|(_, a, _)| *a).max().unwrap_or(0)
+    }
+
+    /// Get the number of transitions leaving each state, empty char transitions included.
+    pub fn out_degree(&self) -> Vec<usize> {
+        let mut degree = vec![0; self.size];
+        self.transitions_with_epsilon()
+            .for_each(|(s, _, _)| degree[s] += 1);
+        degree
+    }
+
+    /// Get the number of transitions entering each state, empty char transitions included.
+    pub fn in_degree(&self) -> Vec<usize> {
+        let mut degree = vec![0; self.size];
+        self.transitions_with_epsilon()
+            .for_each(|(_, _, e)| degree[e] += 1);
+        degree
+    }
+
+    /// Return an order of the states in which every transition, including empty char ones,
+    /// goes from an earlier state to a later one - Using Kahn's algorithm.
+    /// Returns None if the automaton has a cycle.
+    pub fn topological_order(&self) -> Option<Vec<usize>> {
+        let mut in_degree = vec![0; self.size];
+        let mut successors: Vec<Vec<usize>> = vec![Vec::new(); self.size];
+        for (s, _, e) in self.transitions_with_epsilon() {
+            in_degree[e] += 1;
+            successors[s].push(e);
+        }
+
+        let mut queue: VecDeque<usize> = (0..self.size).filter(|s| in_degree[*s] == 0).collect();
+        let mut order = Vec::with_capacity(self.size);
+        while let Some(s) = queue.pop_front() {
+            order.push(s);
+            for e in &successors[s] {
+                in_degree[*e] -= 1;
+                if in_degree[*e] == 0 {
+                    queue.push_back(*e);
+                }
+            }
+        }
+        if order.len() == self.size {
+            Some(order)
+        } else {
+            None
+        }
+    }
+
+    /// Check whether the transition graph of the automaton has no cycle.
+    pub fn is_acyclic(&self) -> bool {
+        self.topological_order().is_some()
+    }
+
+    /// Get the strongly connected components of the transition graph, ignoring letters -
+    /// Using Tarjan's algorithm. Components are returned in reverse topological order, each
+    /// with its states sorted.
+    pub fn sccs(&self) -> Vec<Vec<usize>> {
+        let mut successors: Vec<Vec<usize>> = vec![Vec::new(); self.size];
+        self.transitions_with_epsilon()
+            .for_each(|(s, _, e)| successors[s].push(e));
+        Self::sccs_of(&successors)
+    }
+
+    /// Check whether the automaton recognizes finitely many words - That is, whether no cycle
+    /// reading a letter goes through a state both reachable from a start state and able to reach
+    /// an accept state. Cycles of empty char transitions only do not make the language infinite.
+    pub fn is_finite_language(&self) -> bool {
+        let arr = self.get_transition_array();
+        let live = self.get_live_states();
+        let mut useful = self.get_successor_states(&self.start);
+        useful.extend(&self.start);
+        useful.retain(|s| live.contains(s));
+
+        let mut successors: Vec<Vec<usize>> = vec![Vec::new(); self.size];
+        for letter_arr in &arr {
+            for s in &useful {
+                successors[*s].extend(letter_arr[*s].iter().filter(|e| useful.contains(e)));
+            }
+        }
+        let mut component = vec![0; self.size];
+        for (i, states) in Self::sccs_of(&successors).iter().enumerate() {
+            states.iter().for_each(|s| component[*s] = i);
+        }
+        !arr.iter().skip(1).any(|letter_arr| {
+            useful.iter().any(|s| {
+                letter_arr[*s]
+                    .iter()
+                    .any(|e| useful.contains(e) && component[*e] == component[*s])
+            })
+        })
+    }
+
+    /// Lazily enumerate the accepted words of length at most `max_len`, in length-lexicographic
+    /// order - A breadth first search over words, each paired with the set of states it reaches
+    /// closed over empty char transitions, so every word is visited once. Words whose set cannot
+    /// reach an accept state are not extended.
+    pub fn words_up_to(&self, max_len: usize) -> impl Iterator<Item = Vec<usize>> + '_ {
+        let arr = self.get_transition_array();
+        let mut live = vec![false; self.size];
+        self.get_live_states()
+            .into_iter()
+            .for_each(|s| live[s] = true);
+        let start_set = self.get_start_set(&arr);
+        let mut queue = VecDeque::new();
+        if start_set.get_seq().iter().any(|s| live[*s]) {
+            queue.push_back((vec![], start_set));
+        }
+        AcceptedWords {
+            aut: self,
+            arr,
+            live,
+            queue,
+            max_len,
+        }
+    }
+
+    /// Get the strongly connected components of a graph given by the successors of each node.
+    fn sccs_of(successors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        let size = successors.len();
+        let mut index: Vec<Option<usize>> = vec![None; size];
+        let mut low_link: Vec<usize> = vec![0; size];
+        let mut on_stack: Vec<bool> = vec![false; size];
+        let mut stack: Vec<usize> = Vec::new();
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        let mut counter = 0;
+
+        for root in 0..size {
+            if index[root].is_some() {
+                continue;
+            }
+            // Explicit call stack of (state, next successor to visit) to avoid deep recursion.
+            let mut call_stack: Vec<(usize, usize)> = vec![(root, 0)];
+            index[root] = Some(counter);
+            low_link[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((v, i)) = call_stack.last_mut() {
+                let v = *v;
+                if *i < successors[v].len() {
+                    let w = successors[v][*i];
+                    *i += 1;
+                    match index[w] {
+                        None => {
+                            index[w] = Some(counter);
+                            low_link[w] = counter;
+                            counter += 1;
+                            stack.push(w);
+                            on_stack[w] = true;
+                            call_stack.push((w, 0));
+                        }
+                        Some(index_w) if on_stack[w] => {
+                            low_link[v] = low_link[v].min(index_w);
+                        }
+                        _ => {}
+                    }
+                } else {
+                    call_stack.pop();
+                    if let Some((u, _)) = call_stack.last() {
+                        low_link[*u] = low_link[*u].min(low_link[v]);
+                    }
+                    if Some(low_link[v]) == index[v] {
+                        let mut component = Vec::new();
+                        while let Some(w) = stack.pop() {
+                            on_stack[w] = false;
+                            component.push(w);
+                            if w == v {
+                                break;
+                            }
+                        }
+                        component.sort();
+                        components.push(component);
+                    }
+                }
+            }
+        }
+        components
+    }
+
+    /// Check whether the automaton accepts a word, given its transition array.
+    fn accepts_with(&self, arr: &[Vec<Vec<usize>>], word: &[usize]) -> bool {
+        let mut set = self.get_start_set(arr);
+        for a in word {
+            set = self.get_next_set(arr, &set, *a);
+        }
+        self.end.iter().any(|s| set.bit_at(s))
+    }
+
+    /// Get the classes of states with the same left language, the words leading to them from a
+    /// start state. A state is in the subset reached by a word iff the word is in its left
+    /// language, so states are grouped by the subsets of the superset construction holding them.
+    pub fn left_language_classes(&self) -> Vec<Vec<usize>> {
+        self.subset_membership_classes()
+    }
+
+    /// Get the classes of states with the same right language, the words leading from them to an
+    /// accept state - Using the subsets of the superset construction of the reverse automaton.
+    pub fn right_language_classes(&self) -> Vec<Vec<usize>> {
+        self.clone()
+            .reverse_transitions()
+            .subset_membership_classes()
+    }
+
+    /// Group states by the subsets of the superset construction they belong to.
+    /// Classes are sorted, and ordered by their smallest state.
+    fn subset_membership_classes(&self) -> Vec<Vec<usize>> {
+        let mut membership: Vec<Vec<usize>> = vec![Vec::new(); self.size];
+        let start_set = self.get_start_set(&self.get_transition_array()).get_seq();
+        let discovered = self
+            .determinize_trace()
+            .into_iter()
+            .filter(|step| step.discovered)
+            .map(|step| step.target_set);
+        for (i, set) in std::iter::once(start_set).chain(discovered).enumerate() {
+            set.iter()
+                .filter(|s| **s < self.size)
+                .for_each(|s| membership[*s].push(i));
+        }
+
+        let mut classes: Vec<Vec<usize>> = Vec::new();
+        let mut class_of: HashMap<&Vec<usize>, usize> = HashMap::new();
+        for (s, m) in membership.iter().enumerate() {
+            match class_of.get(m) {
+                Some(c) => classes[*c].push(s),
+                None => {
+                    class_of.insert(m, classes.len());
+                    classes.push(vec![s]);
+                }
+            }
+        }
+        classes
+    }
+
+    /// Get the states reachable from the given states by reading at least one letter.
+    pub(crate) fn get_successor_states(&self, from: &[usize]) -> HashSet<usize> {
+        let arr = self.get_transition_array();
+        let mut reached: HashSet<usize> = HashSet::new();
+        let mut queue: VecDeque<usize> = from.iter().copied().collect();
+        while let Some(s) = queue.pop_front() {
+            for letter_arr in &arr {
+                for e in &letter_arr[s] {
+                    if reached.insert(*e) {
+                        queue.push_back(*e);
+                    }
+                }
+            }
+        }
+        reached
+    }
+
+    /// Get the states from which an accept state can be reached.
+    pub(crate) fn get_live_states(&self) -> HashSet<usize> {
+        let rev_arr = self.get_reverse_transition_arr();
+        let mut live: HashSet<usize> = self.end.iter().copied().collect();
+        let mut queue: VecDeque<usize> = self.end.iter().copied().collect();
+        while let Some(s) = queue.pop_front() {
+            for letter_arr in &rev_arr {
+                for p in &letter_arr[s] {
+                    if live.insert(*p) {
+                        queue.push_back(*p);
+                    }
+                }
+            }
+        }
+        live
+    }
+}
+
+/// Iterator over the accepted words of an automaton up to a length, see `words_up_to`.
+struct AcceptedWords<'a> {
+    aut: &'a Automaton,
+    arr: Vec<Vec<Vec<usize>>>,
+    live: Vec<bool>,
+    queue: VecDeque<(Vec<usize>, Ubig)>,
+    max_len: usize,
+}
+
+impl Iterator for AcceptedWords<'_> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        while let Some((word, set)) = self.queue.pop_front() {
+            if word.len() < self.max_len {
+                for a in 1..self.aut.alphabet + 1 {
+                    let next = self.aut.get_next_set(&self.arr, &set, a);
+                    if next.get_seq().iter().any(|s| self.live[*s]) {
+                        let mut next_word = word.clone();
+                        next_word.push(a);
+                        self.queue.push_back((next_word, next));
+                    }
+                }
+            }
+            if self.aut.end.iter().any(|s| set.bit_at(s)) {
+                return Some(word);
+            }
+        }
+        None
+    }
+}
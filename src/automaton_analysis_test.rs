@@ -0,0 +1,740 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::automaton::{AlgorithmKind, Automaton, AutomatonType};
+    use crate::transition_graphs::{get_buffer_and_stack_aut, get_two_stack_aut};
+
+    #[test]
+    // Test whether an automaton is equivalent to its determinization but not to a different language.
+    fn test_equivalent() {
+        let set_of_states_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            1,
+            vec![(0, 1, 0), (0, 1, 1)],
+            vec![0],
+            vec![1],
+        );
+        let empty_lang_nd = Automaton::new(AutomatonType::NonDet, 1, 1, vec![], vec![0], vec![]);
+        assert!(
+            set_of_states_nd.equivalent(&set_of_states_nd.determinized(AlgorithmKind::Sequential))
+        );
+        assert!(!set_of_states_nd.equivalent(&empty_lang_nd));
+    }
+
+    #[test]
+    // Test whether `a*` as a single loop and as the star of `a` are equivalent, with isomorphic
+    // minimal DFAs, while `a+` is not equivalent to them.
+    fn test_equivalent_a_star() {
+        let a_star_d = Automaton::new(AutomatonType::Det, 1, 1, vec![(0, 1, 0)], vec![0], vec![0]);
+        let a_d = Automaton::new(AutomatonType::Det, 2, 1, vec![(0, 1, 1)], vec![0], vec![1]);
+        let a_star_nd = a_d.kleene_star();
+        let a_plus_d = Automaton::new(
+            AutomatonType::Det,
+            2,
+            1,
+            vec![(0, 1, 1), (1, 1, 1)],
+            vec![0],
+            vec![1],
+        );
+        assert!(a_star_d.equivalent(&a_star_nd));
+        assert!(a_star_nd.equivalent(&a_star_d));
+        assert_eq!(
+            a_star_d.minimized().canonicalize().table,
+            a_star_nd
+                .determinized(AlgorithmKind::Sequential)
+                .minimized()
+                .canonicalize()
+                .table
+        );
+        assert!(!a_star_d.equivalent(&a_plus_d));
+        assert!(!a_star_nd.equivalent(&a_plus_d));
+    }
+
+    #[test]
+    // Test whether a missing transition in a DFA is treated as going to a rejecting sink.
+    fn test_equivalent_incomplete() {
+        let incomplete_d =
+            Automaton::new(AutomatonType::Det, 2, 2, vec![(0, 1, 1)], vec![0], vec![1]);
+        let complete_d = Automaton::new(
+            AutomatonType::Det,
+            3,
+            2,
+            vec![
+                (0, 1, 1),
+                (0, 2, 2),
+                (1, 1, 2),
+                (1, 2, 2),
+                (2, 1, 2),
+                (2, 2, 2),
+            ],
+            vec![0],
+            vec![1],
+        );
+        assert!(incomplete_d.equivalent(&complete_d));
+    }
+
+    #[test]
+    // Test whether the automaton of a single word is ordered along the word, and a* has no order.
+    fn test_topological_order() {
+        let singleton_word_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(1, 2, 2), (0, 1, 1)],
+            vec![0],
+            vec![2],
+        );
+        assert_eq!(singleton_word_nd.topological_order(), Some(vec![0, 1, 2]));
+        assert!(singleton_word_nd.is_acyclic());
+
+        let a_star_d = Automaton::new(AutomatonType::Det, 1, 1, vec![(0, 1, 0)], vec![0], vec![0]);
+        assert_eq!(a_star_d.topological_order(), None);
+        assert!(!a_star_d.is_acyclic());
+    }
+
+    #[test]
+    // Test whether empty char transitions are taken into account when looking for cycles.
+    fn test_topological_order_empty_char() {
+        let empty_char_cycle_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            1,
+            vec![(0, 1, 1), (1, 0, 0)],
+            vec![0],
+            vec![1],
+        );
+        assert!(!empty_char_cycle_nd.is_acyclic());
+    }
+
+    #[test]
+    // Test whether empty char transitions are detected.
+    fn test_has_epsilon() {
+        let empty_char_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            2,
+            vec![
+                (0, 0, 1),
+                (0, 1, 2),
+                (1, 1, 3),
+                (2, 2, 3),
+                (3, 0, 3),
+                (3, 1, 3),
+                (3, 2, 3),
+            ],
+            vec![0],
+            vec![3],
+        );
+        let redundant_d = Automaton::new(
+            AutomatonType::Det,
+            1,
+            2,
+            vec![(0, 1, 0), (0, 2, 0)],
+            vec![0],
+            vec![0],
+        );
+        assert!(empty_char_nd.has_epsilon());
+        assert!(!redundant_d.has_epsilon());
+    }
+
+    #[test]
+    // Test the per-state degrees of the sinkhole automata before and after determinization.
+    fn test_degrees_sinkhole() {
+        let sinkhole_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 1, 2)],
+            vec![0],
+            vec![2],
+        );
+        assert_eq!(sinkhole_nd.out_degree(), vec![1, 1, 0]);
+        assert_eq!(sinkhole_nd.in_degree(), vec![0, 1, 1]);
+
+        let sinkhole_d = Automaton::new(
+            AutomatonType::Det,
+            4,
+            2,
+            vec![
+                (0, 1, 1),
+                (0, 2, 2),
+                (1, 1, 3),
+                (1, 2, 2),
+                (2, 1, 2),
+                (2, 2, 2),
+                (3, 1, 2),
+                (3, 2, 2),
+            ],
+            vec![0],
+            vec![3],
+        );
+        assert_eq!(sinkhole_d.out_degree(), vec![2, 2, 2, 2]);
+        assert_eq!(sinkhole_d.in_degree(), vec![0, 1, 6, 1]);
+    }
+
+    #[test]
+    // Test whether the strongly connected components separate cycles from singleton states.
+    fn test_sccs() {
+        let cycle_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            2,
+            vec![(0, 1, 1), (1, 1, 0), (1, 2, 2), (2, 1, 3), (3, 1, 3)],
+            vec![0],
+            vec![2],
+        );
+        let mut sccs = cycle_nd.sccs();
+        sccs.sort();
+        assert_eq!(sccs, vec![vec![0, 1], vec![2], vec![3]]);
+    }
+
+    #[test]
+    // Test whether every state of a two-stack automaton is in a single component, since the
+    // empty stacks can always be reached back.
+    fn test_sccs_two_stack() {
+        let two_stack = get_two_stack_aut(2, 2);
+        let sccs = two_stack.sccs();
+        assert_eq!(sccs.len(), 1);
+        assert_eq!(sccs[0], (0..two_stack.size).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    // Test whether words are accepted by simulating the set of reachable states, or by walking the
+    // transitions of an incomplete DFA.
+    fn test_accepts() {
+        let empty_char_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 0, 2), (2, 2, 2)],
+            vec![0],
+            vec![2],
+        );
+        assert!(empty_char_nd.accepts(&[1]));
+        assert!(empty_char_nd.accepts(&[1, 2, 2]));
+        assert!(!empty_char_nd.accepts(&[]));
+        assert!(!empty_char_nd.accepts(&[2]));
+        assert!(!empty_char_nd.accepts(&[1, 3]));
+
+        let incomplete_d = Automaton::new(
+            AutomatonType::Det,
+            2,
+            2,
+            vec![(0, 1, 1), (1, 2, 0)],
+            vec![0],
+            vec![0],
+        );
+        assert!(incomplete_d.accepts(&[]));
+        assert!(incomplete_d.accepts(&[1, 2]));
+        assert!(!incomplete_d.accepts(&[1]));
+        assert!(!incomplete_d.accepts(&[2]));
+        assert!(!incomplete_d.accepts(&[0]));
+        assert!(!incomplete_d.accepts(&[1, 3]));
+    }
+
+    #[test]
+    // Test whether the longest accepted prefix follows maximal munch over `{a, aaa}`.
+    fn test_longest_accepted_prefix() {
+        let a_or_aaa_d = Automaton::new(
+            AutomatonType::Det,
+            4,
+            1,
+            vec![(0, 1, 1), (1, 1, 2), (2, 1, 3)],
+            vec![0],
+            vec![1, 3],
+        );
+        assert_eq!(a_or_aaa_d.longest_accepted_prefix(&[1, 1, 1, 1]), Some(3));
+        assert_eq!(a_or_aaa_d.longest_accepted_prefix(&[1, 1]), Some(1));
+        assert_eq!(a_or_aaa_d.longest_accepted_prefix(&[1, 2, 1, 1]), Some(1));
+        assert_eq!(a_or_aaa_d.longest_accepted_prefix(&[]), None);
+        assert_eq!(a_or_aaa_d.longest_accepted_prefix(&[2]), None);
+
+        let empty_word_d = Automaton::new(AutomatonType::Det, 1, 1, vec![], vec![0], vec![0]);
+        assert_eq!(empty_word_d.longest_accepted_prefix(&[1]), Some(0));
+    }
+
+    #[test]
+    // Test whether the accept ratio of a universal automaton is 1, and is reproducible otherwise.
+    fn test_estimate_accept_ratio() {
+        let universal_d = Automaton::new(
+            AutomatonType::Det,
+            1,
+            2,
+            vec![(0, 1, 0), (0, 2, 0)],
+            vec![0],
+            vec![0],
+        );
+        assert_eq!(universal_d.estimate_accept_ratio(8, 100, 7), 1.0);
+
+        let ends_in_a_d = Automaton::new(
+            AutomatonType::Det,
+            2,
+            2,
+            vec![(0, 1, 1), (0, 2, 0), (1, 1, 1), (1, 2, 0)],
+            vec![0],
+            vec![1],
+        );
+        let ratio = ends_in_a_d.estimate_accept_ratio(8, 1000, 7);
+        assert!((ratio - 0.5).abs() < 0.1);
+        assert_eq!(ratio, ends_in_a_d.estimate_accept_ratio(8, 1000, 7));
+    }
+
+    #[test]
+    // Test whether the letters used by the generated automata match their declared alphabets.
+    fn test_alphabet_size_from_table() {
+        for (b, n) in [(1, 1), (2, 2), (3, 2)] {
+            let buffer_and_stack = get_buffer_and_stack_aut(b, n);
+            assert_eq!(
+                buffer_and_stack.alphabet_size_from_table(),
+                buffer_and_stack.alphabet
+            );
+            let two_stack = get_two_stack_aut(b, n);
+            assert_eq!(two_stack.alphabet_size_from_table(), two_stack.alphabet);
+        }
+        let empty_char_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            2,
+            vec![(0, 0, 1)],
+            vec![0],
+            vec![1],
+        );
+        assert_eq!(empty_char_nd.alphabet_size_from_table(), 0);
+    }
+
+    #[test]
+    // Test whether the accept none automaton is empty and the accept all automaton is universal.
+    fn test_accept_none_accept_all() {
+        let none = Automaton::accept_none(3, 2);
+        assert!(none.is_empty_language());
+        assert!(!none.is_universal());
+
+        let all = Automaton::accept_all(3, 2).completed();
+        assert_eq!(all.size, 3);
+        assert!(all.is_universal());
+        assert!(!all.is_empty_language());
+    }
+
+    #[test]
+    // Test emptiness and universality on automata outside of the fast paths.
+    fn test_empty_language_universal() {
+        let unreachable_end_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            1,
+            vec![(1, 1, 1)],
+            vec![0],
+            vec![1],
+        );
+        assert!(unreachable_end_nd.is_empty_language());
+        let a_star_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            1,
+            vec![(0, 1, 0), (0, 1, 1)],
+            vec![0],
+            vec![0],
+        );
+        assert!(a_star_nd.is_universal());
+        let partial_all_d =
+            Automaton::new(AutomatonType::Det, 1, 2, vec![(0, 1, 0)], vec![0], vec![0]);
+        assert!(!partial_all_d.is_universal());
+        assert!(!partial_all_d.is_empty_language());
+    }
+
+    #[test]
+    // Test whether left and right language classes coincide on a symmetric automaton.
+    fn test_language_classes_symmetric() {
+        // Two parallel paths reading a then b, and b then a.
+        let symmetric_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            2,
+            vec![(0, 1, 1), (1, 2, 3), (0, 2, 2), (2, 1, 3)],
+            vec![0],
+            vec![3],
+        );
+        let expected = vec![vec![0], vec![1], vec![2], vec![3]];
+        assert_eq!(symmetric_nd.left_language_classes(), expected);
+        assert_eq!(symmetric_nd.right_language_classes(), expected);
+
+        // Two states reached and left by the same letter share both classes.
+        let merged_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            1,
+            vec![(0, 1, 1), (0, 1, 2), (1, 1, 3), (2, 1, 3)],
+            vec![0],
+            vec![3],
+        );
+        let expected = vec![vec![0], vec![1, 2], vec![3]];
+        assert_eq!(merged_nd.left_language_classes(), expected);
+        assert_eq!(merged_nd.right_language_classes(), expected);
+    }
+
+    #[test]
+    // Test whether {a, b} is prefix free and {a, ab} is not.
+    fn test_is_prefix_free() {
+        let a_or_b_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            2,
+            vec![(0, 1, 1), (0, 2, 1)],
+            vec![0],
+            vec![1],
+        );
+        assert!(a_or_b_nd.is_prefix_free());
+        let a_or_ab_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 2, 2)],
+            vec![0],
+            vec![1, 2],
+        );
+        assert!(!a_or_ab_nd.is_prefix_free());
+    }
+
+    #[test]
+    // Test whether the dead state of completed automata is found only when there is one.
+    fn test_dead_state() {
+        let partial_d = Automaton::new(
+            AutomatonType::Det,
+            2,
+            2,
+            vec![(0, 1, 1), (1, 2, 1)],
+            vec![0],
+            vec![1],
+        );
+        let (complete_d, sink) = partial_d.complete_to_sink_id();
+        assert_eq!(complete_d.dead_state(), sink);
+        assert_eq!(partial_d.dead_state(), None);
+
+        let no_dead_d = Automaton::accept_all(2, 2);
+        assert_eq!(no_dead_d.completed().dead_state(), None);
+        let two_dead_d = Automaton::accept_none(2, 2);
+        assert_eq!(two_dead_d.completed().dead_state(), None);
+    }
+
+    #[test]
+    // Test whether only letters leading to accept states are reachable, and none from a sink.
+    fn test_letters_reachable_from() {
+        let sinkhole_d = Automaton::new(
+            AutomatonType::Det,
+            4,
+            2,
+            vec![
+                (0, 1, 1),
+                (0, 2, 2),
+                (1, 1, 3),
+                (1, 2, 2),
+                (2, 1, 2),
+                (2, 2, 2),
+                (3, 1, 2),
+                (3, 2, 2),
+            ],
+            vec![0],
+            vec![3],
+        );
+        assert_eq!(sinkhole_d.letters_reachable_from(0), HashSet::from([1]));
+        assert_eq!(sinkhole_d.letters_reachable_from(3), HashSet::new());
+        assert!(sinkhole_d.letters_reachable_from(2).is_empty());
+    }
+
+    #[test]
+    // Test whether the distinguishing word of two automata is found with the side accepting it.
+    fn test_distinguishing_witness() {
+        let one_two_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 2, 2)],
+            vec![0],
+            vec![2],
+        );
+        let one_two_or_two_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 2, 2), (0, 2, 2)],
+            vec![0],
+            vec![2],
+        );
+        assert_eq!(
+            one_two_nd.distinguishing_witness(&one_two_or_two_nd),
+            Some((vec![2], false))
+        );
+        assert_eq!(
+            one_two_or_two_nd.distinguishing_witness(&one_two_nd),
+            Some((vec![2], true))
+        );
+        assert_eq!(
+            one_two_nd.distinguishing_witness(&one_two_nd.determinized(AlgorithmKind::Sequential)),
+            None
+        );
+    }
+
+    #[test]
+    // Test whether large automata sharing a single short word have a non empty intersection.
+    fn test_intersection_is_empty() {
+        // Accepts the words of `len` times `letter`, and `1 2` if asked to.
+        let chain = |len: usize, letter: usize, with_one_two: bool| {
+            let mut table: Vec<(usize, usize, usize)> =
+                (0..len).map(|s| (s, letter, s + 1)).collect();
+            let mut end = vec![len];
+            if with_one_two {
+                table.extend([(0, 1, len + 1), (len + 1, 2, len + 2)]);
+                end.push(len + 2);
+            }
+            Automaton::new(AutomatonType::NonDet, len + 3, 2, table, vec![0], end)
+        };
+        let ones = chain(500, 1, true);
+        assert!(!ones.intersection_is_empty(&chain(499, 2, true)));
+        assert!(ones.intersection_is_empty(&chain(499, 2, false)));
+        assert!(!ones.intersection_is_empty(&chain(500, 1, false)));
+        assert!(Automaton::accept_all(1, 2).intersection_is_empty(&Automaton::accept_none(1, 2)));
+    }
+
+    #[test]
+    // Test whether the fooling set of a minimal DFA holds all of its states, and of a
+    // minimizable DFA as many states as its minimization.
+    fn test_fooling_set() {
+        let bipartite_small = Automaton::new(
+            AutomatonType::Det,
+            2,
+            2,
+            vec![(0, 1, 1), (0, 2, 1), (1, 1, 1), (1, 2, 1)],
+            vec![0],
+            vec![1],
+        );
+        assert_eq!(bipartite_small.fooling_set(), vec![0, 1]);
+
+        let bipartite_big = Automaton::new(
+            AutomatonType::Det,
+            3,
+            2,
+            vec![
+                (0, 1, 1),
+                (0, 2, 2),
+                (1, 1, 1),
+                (1, 2, 1),
+                (2, 1, 2),
+                (2, 2, 2),
+            ],
+            vec![0],
+            vec![1, 2],
+        );
+        assert_eq!(bipartite_big.fooling_set(), vec![0, 1]);
+        assert_eq!(
+            bipartite_big.fooling_set().len(),
+            bipartite_big.minimized().size
+        );
+
+        let mut unreachable_big = bipartite_big.clone();
+        unreachable_big.size = 4;
+        unreachable_big.table.extend([(3, 1, 3), (3, 2, 0)]);
+        assert_eq!(unreachable_big.fooling_set(), vec![0, 1]);
+    }
+
+    #[test]
+    // Test whether an unrolled loop is bisimilar to the loop, and whether choosing between `ab`
+    // and `ac` early is not bisimilar to choosing late though both are equivalent.
+    fn test_is_bisimilar_to() {
+        let loop_nd = Automaton::new(
+            AutomatonType::NonDet,
+            1,
+            1,
+            vec![(0, 1, 0)],
+            vec![0],
+            vec![0],
+        );
+        let unrolled_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            1,
+            vec![(0, 1, 1), (1, 1, 0)],
+            vec![0],
+            vec![0, 1],
+        );
+        assert!(loop_nd.is_bisimilar_to(&unrolled_nd));
+        assert!(unrolled_nd.is_bisimilar_to(&loop_nd));
+
+        let early_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            3,
+            vec![(0, 1, 1), (0, 1, 2), (1, 2, 3), (2, 3, 3)],
+            vec![0],
+            vec![3],
+        );
+        let late_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            3,
+            vec![(0, 1, 1), (1, 2, 2), (1, 3, 2)],
+            vec![0],
+            vec![2],
+        );
+        assert!(early_nd.equivalent(&late_nd));
+        assert!(!early_nd.is_bisimilar_to(&late_nd));
+        assert!(early_nd.is_bisimilar_to(&early_nd));
+    }
+
+    #[test]
+    // Test whether a state with no transitions, one with only an empty char transition and a
+    // final state are reported as trap states, and only the first is a dead end.
+    fn test_trap_states() {
+        let dead_end_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            2,
+            vec![(0, 1, 1), (0, 2, 2), (2, 0, 3)],
+            vec![0],
+            vec![3],
+        );
+        let traps = dead_end_nd.trap_states();
+        assert_eq!(traps, vec![1, 2, 3]);
+        let dead_ends: Vec<usize> = traps
+            .into_iter()
+            .filter(|s| !dead_end_nd.end.contains(s))
+            .filter(|s| dead_end_nd.table.iter().all(|t| t.0 != *s))
+            .collect();
+        assert_eq!(dead_ends, vec![1]);
+    }
+
+    #[test]
+    // Test whether finiteness tells apart a language of a single word from a+, ignoring cycles
+    // that cannot reach an accept state and cycles of empty char transitions.
+    fn test_is_finite_language() {
+        let mut ab_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            2,
+            vec![(0, 1, 1), (1, 2, 2), (3, 1, 3), (0, 2, 3)],
+            vec![0],
+            vec![2],
+        );
+        assert!(ab_nd.is_finite_language());
+        assert!(!ab_nd.is_empty_language());
+        ab_nd.epsilon_table = Some(vec![(1, 0), (0, 1)]);
+        assert!(!ab_nd.is_finite_language());
+
+        let a_plus_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            1,
+            vec![(0, 1, 1), (1, 1, 1)],
+            vec![0],
+            vec![1],
+        );
+        assert!(!a_plus_nd.is_finite_language());
+
+        let epsilon_loop_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            1,
+            vec![(0, 0, 1), (1, 0, 0)],
+            vec![0],
+            vec![1],
+        );
+        assert!(epsilon_loop_nd.is_finite_language());
+        assert!(Automaton::accept_none(3, 2).is_finite_language());
+    }
+
+    #[test]
+    // Test whether accepted words are enumerated in length-lexicographic order, once each, up to
+    // the given length, and whether the sequential determinization keeps the same words.
+    fn test_words_up_to() {
+        // Words over {a, b} ending with a, with two ways to read each of them.
+        let ends_with_a_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![
+                (0, 1, 0),
+                (0, 2, 0),
+                (0, 1, 1),
+                (0, 0, 2),
+                (2, 1, 1),
+                (2, 2, 2),
+            ],
+            vec![0],
+            vec![1],
+        );
+        let words: Vec<Vec<usize>> = ends_with_a_nd.words_up_to(2).collect();
+        assert_eq!(words, vec![vec![1], vec![1, 1], vec![2, 1]]);
+        assert_eq!(ends_with_a_nd.words_up_to(0).count(), 0);
+        assert_eq!(
+            ends_with_a_nd.words_up_to(usize::MAX).nth(3),
+            Some(vec![1, 1, 1])
+        );
+
+        let dfa = ends_with_a_nd.determinized(AlgorithmKind::Sequential);
+        assert!(dfa.words_up_to(5).eq(ends_with_a_nd.words_up_to(5)));
+        assert_eq!(dfa.words_up_to(5).count(), 31);
+        assert_eq!(Automaton::accept_none(3, 2).words_up_to(5).count(), 0);
+    }
+
+    #[test]
+    // Test whether the shortest word skips a longer path, follows empty char transitions for
+    // free, is empty when the empty word is accepted, and is None for the empty language.
+    fn test_shortest_word() {
+        let two_paths_nd = Automaton::new(
+            AutomatonType::NonDet,
+            5,
+            2,
+            vec![(0, 1, 1), (1, 1, 2), (2, 1, 4), (0, 0, 3), (3, 2, 4)],
+            vec![0],
+            vec![4],
+        );
+        assert_eq!(two_paths_nd.shortest_word(), Some(vec![2]));
+        assert!(two_paths_nd.accepts(&[2]));
+
+        let mut epsilon_accept_nd = two_paths_nd.clone();
+        epsilon_accept_nd.epsilon_table = Some(vec![(3, 4)]);
+        assert_eq!(epsilon_accept_nd.shortest_word(), Some(vec![]));
+
+        let unreachable_end_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            1,
+            vec![(1, 1, 1)],
+            vec![0],
+            vec![1],
+        );
+        assert_eq!(unreachable_end_nd.shortest_word(), None);
+        assert_eq!(Automaton::accept_none(3, 2).shortest_word(), None);
+
+        let bns = get_buffer_and_stack_aut(2, 2);
+        let word = bns.shortest_word().unwrap();
+        assert!(bns.accepts(&word));
+        assert_eq!(
+            bns.words_up_to(word.len()).next().map(|w| w.len()),
+            Some(word.len())
+        );
+    }
+
+    #[test]
+    // Test whether the degrees, cycles, components and dead state account for the empty char
+    // transitions of the epsilon table.
+    fn test_graph_helpers_epsilon_table() {
+        let mut epsilon_table_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            1,
+            vec![(0, 1, 1), (2, 1, 2)],
+            vec![0],
+            vec![1],
+        );
+        assert_eq!(epsilon_table_nd.dead_state(), Some(2));
+        epsilon_table_nd.epsilon_table = Some(vec![(1, 0), (2, 1)]);
+
+        assert_eq!(epsilon_table_nd.out_degree(), vec![1, 1, 2]);
+        assert_eq!(epsilon_table_nd.in_degree(), vec![1, 2, 1]);
+        assert_eq!(epsilon_table_nd.topological_order(), None);
+        assert_eq!(epsilon_table_nd.sccs(), vec![vec![0, 1], vec![2]]);
+        assert_eq!(epsilon_table_nd.dead_state(), None);
+    }
+}
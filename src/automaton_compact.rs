@@ -0,0 +1,189 @@
+use std::{collections::VecDeque, fmt::Debug};
+
+use crate::{
+    automaton::{Automaton, AutomatonType},
+    automaton_sequential::HashMapXX,
+    ubig::{CompressedUbig, Ubig},
+};
+
+/// Integer types usable for the states and letters of a compact automaton.
+pub trait StateId: Copy + Eq + Ord + Debug {
+    /// Convert from a usize, or None if the value does not fit.
+    fn from_usize(n: usize) -> Option<Self>;
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! impl_state_id {
+    ($($t:ty),*) => {
+        $(impl StateId for $t {
+            fn from_usize(n: usize) -> Option<Self> {
+                <$t>::try_from(n).ok()
+            }
+            fn to_usize(self) -> usize {
+                self as usize
+            }
+        })*
+    };
+}
+impl_state_id!(u8, u16, u32, u64, usize);
+
+// Structure for an automaton storing states and letters in a chosen integer type.
+// Determinization runs on the tables in that type and numbers the subsets it discovers with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactAutomaton<T: StateId> {
+    pub automaton_type: AutomatonType,
+    pub size: usize,
+    pub alphabet: usize,
+    pub table: Vec<(T, T, T)>,
+    pub start: Vec<T>,
+    pub end: Vec<T>,
+    /// Empty char transitions `(source, target)` kept apart from `table`.
+    pub epsilon_table: Option<Vec<(T, T)>>,
+    pub alphabet_labels: Option<Vec<String>>,
+}
+
+impl<T: StateId> CompactAutomaton<T> {
+    /// Return the compact version of an automaton, or None if a state or letter does not fit in T.
+    /// Accept priorities and transition probabilities are not kept.
+    pub fn from_automaton(aut: &Automaton) -> Option<CompactAutomaton<T>> {
+        let convert_vec =
+            |v: &Vec<usize>| -> Option<Vec<T>> { v.iter().map(|s| T::from_usize(*s)).collect() };
+        Some(CompactAutomaton {
+            automaton_type: aut.automaton_type.clone(),
+            size: aut.size,
+            alphabet: aut.alphabet,
+            table: aut
+                .table
+                .iter()
+                .map(|(s, a, e)| Some((T::from_usize(*s)?, T::from_usize(*a)?, T::from_usize(*e)?)))
+                .collect::<Option<Vec<(T, T, T)>>>()?,
+            start: convert_vec(&aut.start)?,
+            end: convert_vec(&aut.end)?,
+            epsilon_table: match &aut.epsilon_table {
+                Some(epsilon_table) => Some(
+                    epsilon_table
+                        .iter()
+                        .map(|(s, e)| Some((T::from_usize(*s)?, T::from_usize(*e)?)))
+                        .collect::<Option<Vec<(T, T)>>>()?,
+                ),
+                None => None,
+            },
+            alphabet_labels: aut.alphabet_labels.clone(),
+        })
+    }
+
+    /// Return the automaton with usize states and letters.
+    pub fn to_automaton(&self) -> Automaton {
+        let convert_vec = |v: &Vec<T>| -> Vec<usize> { v.iter().map(|s| s.to_usize()).collect() };
+        let mut ret = Automaton::new(
+            self.automaton_type.clone(),
+            self.size,
+            self.alphabet,
+            self.table
+                .iter()
+                .map(|(s, a, e)| (s.to_usize(), a.to_usize(), e.to_usize()))
+                .collect(),
+            convert_vec(&self.start),
+            convert_vec(&self.end),
+        );
+        ret.epsilon_table = self.epsilon_table.as_ref().map(|epsilon_table| {
+            epsilon_table
+                .iter()
+                .map(|(s, e)| (s.to_usize(), e.to_usize()))
+                .collect()
+        });
+        ret.alphabet_labels = self.alphabet_labels.clone();
+        ret
+    }
+
+    /// Return a determinized version of the automaton, or None if the DFA does not fit in T.
+    /// The superset construction runs sequentially on the tables in T, without converting the
+    /// automaton to usize states.
+    pub fn determinized(&self) -> Option<CompactAutomaton<T>> {
+        if self.automaton_type == AutomatonType::Det {
+            return Some(self.clone());
+        }
+        let arr = self.get_transition_array();
+        let letters = (1..self.alphabet + 1)
+            .map(T::from_usize)
+            .collect::<Option<Vec<T>>>()?;
+        let mut num_mapper: HashMapXX<CompressedUbig, T> = HashMapXX::default();
+        let mut frontier: VecDeque<(T, Ubig)> = VecDeque::new();
+        let mut table: Vec<(T, T, T)> = Vec::new();
+        let mut end: Vec<T> = Vec::new();
+
+        // Number a subset with the next id if it is new, and push it onto the frontier.
+        let mut discover = |set: Ubig, frontier: &mut VecDeque<(T, Ubig)>| -> Option<T> {
+            let key = set.clone().compress();
+            if let Some(id) = num_mapper.get(&key) {
+                return Some(*id);
+            }
+            let id = T::from_usize(num_mapper.len())?;
+            num_mapper.insert(key, id);
+            if self.end.iter().any(|s| set.bit_at(&s.to_usize())) {
+                end.push(id);
+            }
+            frontier.push_back((id, set));
+            Some(id)
+        };
+
+        let mut start_set = Ubig::new();
+        self.start
+            .iter()
+            .for_each(|s| Self::add_state(&arr, &mut start_set, *s));
+        let start = discover(start_set, &mut frontier)?;
+        while let Some((id, set)) = frontier.pop_front() {
+            let seq = set.get_seq();
+            for a in &letters {
+                let mut next = Ubig::new();
+                seq.iter().for_each(|s| {
+                    arr[a.to_usize()][*s]
+                        .iter()
+                        .for_each(|t| Self::add_state(&arr, &mut next, *t))
+                });
+                table.push((id, *a, discover(next, &mut frontier)?));
+            }
+        }
+        Some(CompactAutomaton {
+            automaton_type: AutomatonType::Det,
+            size: num_mapper.len(),
+            alphabet: self.alphabet,
+            table,
+            start: vec![start],
+            end,
+            epsilon_table: None,
+            alphabet_labels: self.alphabet_labels.clone(),
+        })
+    }
+
+    /// Get the targets of each state by letter, empty char transitions being letter 0.
+    fn get_transition_array(&self) -> Vec<Vec<Vec<T>>> {
+        let mut arr: Vec<Vec<Vec<T>>> = vec![vec![Vec::new(); self.size]; self.alphabet + 1];
+        self.table
+            .iter()
+            .for_each(|(s, a, e)| arr[a.to_usize()][s.to_usize()].push(*e));
+        self.epsilon_table
+            .iter()
+            .flatten()
+            .for_each(|(s, e)| arr[0][s.to_usize()].push(*e));
+        arr
+    }
+
+    /// Add a state to a set of states, along with the states reached from it by empty char
+    /// transitions.
+    fn add_state(arr: &[Vec<Vec<T>>], set: &mut Ubig, state: T) {
+        let mut stack = vec![state];
+        while let Some(s) = stack.pop() {
+            if !set.bit_at(&s.to_usize()) {
+                set.set_to(&s.to_usize(), true);
+                stack.extend(&arr[0][s.to_usize()]);
+            }
+        }
+    }
+
+    /// Return a minimized version of the automaton, or None if it does not fit in T - Partial
+    /// DFAs are completed with a sink state first, which can add a state.
+    pub fn minimized(&self) -> Option<CompactAutomaton<T>> {
+        CompactAutomaton::from_automaton(&self.to_automaton().minimized())
+    }
+}
@@ -0,0 +1,143 @@
+#[cfg(test)]
+mod tests {
+    use crate::automaton::{AlgorithmKind, Automaton, AutomatonType};
+    use crate::automaton_compact::CompactAutomaton;
+
+    fn get_sinkhole_nd() -> Automaton {
+        Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 1, 2)],
+            vec![0],
+            vec![2],
+        )
+    }
+
+    #[test]
+    // Test whether u32 and usize compact automata determinize to the same DFA as the automaton.
+    fn test_compact_determinization() {
+        let sinkhole_nd = get_sinkhole_nd();
+        let expected = sinkhole_nd
+            .determinized(AlgorithmKind::Sequential)
+            .order_transitions();
+        let compact_u32 = CompactAutomaton::<u32>::from_automaton(&sinkhole_nd).unwrap();
+        let compact_usize = CompactAutomaton::<usize>::from_automaton(&sinkhole_nd).unwrap();
+
+        let det_u32 = compact_u32
+            .determinized()
+            .unwrap()
+            .to_automaton()
+            .order_transitions();
+        let det_usize = compact_usize
+            .determinized()
+            .unwrap()
+            .to_automaton()
+            .order_transitions();
+        assert_eq!(det_u32.table, expected.table);
+        assert_eq!(det_usize.table, expected.table);
+        assert_eq!(det_u32.end, det_usize.end);
+    }
+
+    #[test]
+    // Test whether automata with states that do not fit the integer type are rejected.
+    fn test_compact_overflow() {
+        let large = Automaton::new(
+            AutomatonType::NonDet,
+            300,
+            1,
+            vec![(0, 1, 299)],
+            vec![0],
+            vec![299],
+        );
+        assert!(CompactAutomaton::<u8>::from_automaton(&large).is_none());
+        assert!(CompactAutomaton::<u16>::from_automaton(&large).is_some());
+    }
+
+    #[test]
+    // Test whether minimizing a partial DFA that fits in u8 only until its sink is added gives
+    // None rather than panicking.
+    fn test_compact_minimized_overflow() {
+        let chain_d = Automaton::new(
+            AutomatonType::Det,
+            256,
+            1,
+            (0..255).map(|s| (s, 1, s + 1)).collect(),
+            vec![0],
+            vec![255],
+        );
+        let compact_u8 = CompactAutomaton::<u8>::from_automaton(&chain_d).unwrap();
+        assert!(compact_u8.minimized().is_none());
+
+        let compact_u16 = CompactAutomaton::<u16>::from_automaton(&chain_d).unwrap();
+        assert_eq!(compact_u16.minimized().unwrap().size, 257);
+    }
+
+    #[test]
+    // Test whether an NFA with its empty char transitions kept apart from the table keeps its
+    // language through the compact form.
+    fn test_compact_epsilon_table() {
+        let mut a_eps_b = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            2,
+            vec![(0, 1, 1), (2, 2, 3)],
+            vec![0],
+            vec![3],
+        );
+        a_eps_b.epsilon_table = Some(vec![(1, 2)]);
+        let compact_u8 = CompactAutomaton::<u8>::from_automaton(&a_eps_b).unwrap();
+        let round_trip = compact_u8.to_automaton();
+        assert_eq!(round_trip.epsilon_table, Some(vec![(1, 2)]));
+        assert!(round_trip.accepts(&[1, 2]));
+        assert!(!round_trip.accepts(&[1]));
+        assert!(round_trip.equivalent(&a_eps_b));
+    }
+
+    #[test]
+    // Test whether letter names go through the compact form and determinization, while accept
+    // priorities and transition probabilities are dropped.
+    fn test_compact_alphabet_labels() {
+        let mut sinkhole_nd = get_sinkhole_nd();
+        sinkhole_nd.alphabet_labels = Some(vec!["a".to_string(), "b".to_string()]);
+        sinkhole_nd.accept_priority = Some(vec![0]);
+        sinkhole_nd.probabilities = Some(vec![1.0, 1.0]);
+        let compact_u8 = CompactAutomaton::<u8>::from_automaton(&sinkhole_nd).unwrap();
+        assert_eq!(compact_u8.alphabet_labels, sinkhole_nd.alphabet_labels);
+
+        let round_trip = compact_u8.to_automaton();
+        assert_eq!(round_trip.alphabet_labels, sinkhole_nd.alphabet_labels);
+        assert_eq!(round_trip.accept_priority, None);
+        assert_eq!(round_trip.probabilities, None);
+
+        let det = compact_u8.determinized().unwrap();
+        assert_eq!(det.alphabet_labels, sinkhole_nd.alphabet_labels);
+    }
+
+    #[test]
+    // Test whether determinizing in the compact form follows the empty char transitions, and
+    // gives None once the DFA has more states than T can number.
+    fn test_compact_determinization_overflow() {
+        // The ninth letter from the end is a 1: the DFA needs 2^9 states.
+        let mut ninth_last = Automaton::new(
+            AutomatonType::NonDet,
+            11,
+            2,
+            (1..10).map(|s| (s, 1, s + 1)).collect(),
+            vec![0],
+            vec![10],
+        );
+        ninth_last.table.push((0, 1, 0));
+        ninth_last.table.push((0, 2, 0));
+        ninth_last.table.extend((2..10).map(|s| (s, 2, s + 1)));
+        ninth_last.epsilon_table = Some(vec![(0, 1)]);
+        let expected = ninth_last.determinized(AlgorithmKind::Sequential);
+
+        let compact_u8 = CompactAutomaton::<u8>::from_automaton(&ninth_last).unwrap();
+        assert!(compact_u8.determinized().is_none());
+        let compact_u16 = CompactAutomaton::<u16>::from_automaton(&ninth_last).unwrap();
+        let det_u16 = compact_u16.determinized().unwrap();
+        assert_eq!(det_u16.size, expected.size);
+        assert!(det_u16.to_automaton().equivalent(&expected));
+    }
+}
@@ -1,117 +1,725 @@
 extern crate pest;
 
-use pest::Parser;
+use lz4_flex::decompress_size_prepended;
+use pest::{error::LineColLocation, iterators::Pair, Parser};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    error::Error,
+    fmt, fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
 
-use crate::automaton::{Automaton, AutomatonType};
+use crate::{
+    automaton::{Automaton, AutomatonType},
+    ubig::Ubig,
+};
+
+/// Letters used to write the alphabet of automata with empty char transitions.
+const LETTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Edge between two states labelled with ranges of consecutive letters `(first, last)`.
+pub type MergedEdge = (usize, usize, Vec<(usize, usize)>);
 
 #[derive(pest_derive::Parser)]
 #[grammar = "automaton.pest"]
 struct AutomatonParser;
 
+/// Convention for the state numbers written in an automaton file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexBase {
+    /// States are numbered from 0, as they are internally.
+    Zero,
+    /// States are numbered from 1, as in GAP - They are shifted down by one when parsed.
+    One,
+}
+
+/// Error found while parsing an automaton, at a line and column of the input counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+/// What failed while parsing an automaton.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The input does not follow the grammar of the automaton format.
+    Syntax(String),
+    /// A size, alphabet or state number does not fit in a `usize`.
+    NumberTooLarge(String),
+    /// A state is numbered below the index base.
+    StateOutOfRange(usize),
+    /// A letter of the transition table is beyond the alphabet.
+    LetterOutOfRange { letter: usize, alphabet: usize },
+}
+
+impl ParseError {
+    fn at(pair: &Pair<Rule>, kind: ParseErrorKind) -> ParseError {
+        let (line, column) = pair.as_span().start_pos().line_col();
+        ParseError { line, column, kind }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            ParseErrorKind::Syntax(msg) => write!(f, "{}", msg),
+            ParseErrorKind::NumberTooLarge(num) => write!(f, "Number {} is too large!", num),
+            ParseErrorKind::StateOutOfRange(s) => {
+                write!(f, "State {} is below the index base!", s)
+            }
+            ParseErrorKind::LetterOutOfRange { letter, alphabet } => write!(
+                f,
+                "Letter {} is out of range for an alphabet of {} letters!",
+                letter, alphabet
+            ),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parse an automaton from the GAP automaton format, with states numbered from 1.
+/// Prints the error and gives an empty automaton if parsing fails - See `TryFrom<&str>`.
 impl From<&String> for Automaton {
     fn from(s: &String) -> Self {
-        return match AutomatonParser::parse(Rule::automaton, s) {
-            Ok(mut pairs) => {
-                // Get contents of automaton from automaton -> core -> inner
-                let mut contents = pairs
-                    .next()
-                    .unwrap()
-                    .into_inner()
-                    .next()
-                    .unwrap()
-                    .into_inner();
-                let mut ret = Automaton::empty();
-
-                // Get the pairs for all the properties of the automaton.
-                ret.automaton_type = match contents.next().unwrap().as_str() {
-                    "det" => AutomatonType::Det,
-                    "nondet" => AutomatonType::NonDet,
-                    "epsilon" => AutomatonType::NonDet,
-                    _ => panic!("Unreachable code!"),
-                };
+        Automaton::parse_with_base(s, IndexBase::One)
+    }
+}
 
-                // Set size and alphabet.
-                ret.size = str::parse(contents.next().unwrap().as_str()).unwrap();
-                let alphabet_parse = contents.next().unwrap();
-                match alphabet_parse.as_rule() {
-                    Rule::LETTER_STR => {
-                        ret.alphabet = alphabet_parse.as_str().len();
-                    }
-                    Rule::NUM => {
-                        ret.alphabet = str::parse(alphabet_parse.as_str()).unwrap();
-                    }
-                    _ => {
-                        panic!("Unreachable code!");
-                    }
+/// Parse an automaton from the GAP automaton format, with states numbered from 1.
+impl TryFrom<&str> for Automaton {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Automaton::try_parse_with_base(s, IndexBase::One)
+    }
+}
+
+impl Automaton {
+    /// Parse an automaton from the GAP automaton format - See `try_parse_with_base`.
+    /// Prints the error and gives an empty automaton if parsing fails.
+    pub fn parse_with_base(s: &str, base: IndexBase) -> Automaton {
+        Automaton::try_parse_with_base(s, base).unwrap_or_else(|error| {
+            println!("{}", error);
+            Automaton::empty()
+        })
+    }
+
+    /// Parse an automaton from the GAP automaton format. The `i`-th row of each letter holds the
+    /// targets of the `i`-th state, and the rows are numbered from the index base like the
+    /// target, start and accept states. All of them are converted to internal states numbered
+    /// from 0, and the declared size is grown to cover the largest state referenced.
+    /// A row of the transition table prefixed with `*` is a wildcard, giving each state targets
+    /// on every letter it has no more specific transition on.
+    /// Lines starting with `#` between the elements of the automaton are comments.
+    /// Returns an error if the input does not follow the format, a number is too large, a state
+    /// is numbered below the index base, or there are more letters than the alphabet holds.
+    pub fn try_parse_with_base(s: &str, base: IndexBase) -> Result<Automaton, ParseError> {
+        let offset = match base {
+            IndexBase::Zero => 0,
+            IndexBase::One => 1,
+        };
+        let mut pairs = AutomatonParser::parse(Rule::automaton, s).map_err(|error| {
+            let (line, column) = match error.line_col {
+                LineColLocation::Pos(pos) | LineColLocation::Span(pos, _) => pos,
+            };
+            ParseError {
+                line,
+                column,
+                kind: ParseErrorKind::Syntax(error.variant.message().to_string()),
+            }
+        })?;
+        // Get contents of automaton from automaton -> core -> inner
+        let mut contents = pairs
+            .next()
+            .unwrap()
+            .into_inner()
+            .next()
+            .unwrap()
+            .into_inner();
+        let mut ret = Automaton::empty();
+
+        // Get the pairs for all the properties of the automaton. The grammar guarantees each of
+        // them is there.
+        ret.automaton_type = match contents.next().unwrap().as_str() {
+            "det" => AutomatonType::Det,
+            _ => AutomatonType::NonDet,
+        };
+
+        // Set size and alphabet.
+        ret.size = Automaton::parse_num(&contents.next().unwrap())?;
+        let alphabet_parse = contents.next().unwrap();
+        ret.alphabet = match alphabet_parse.as_rule() {
+            Rule::LETTER_STR => alphabet_parse.as_str().len(),
+            _ => Automaton::parse_num(&alphabet_parse)?,
+        };
+        // Letters written as a string are named after their character, the empty char aside.
+        if alphabet_parse.as_rule() == Rule::LETTER_STR {
+            ret.alphabet_labels = Some(
+                alphabet_parse
+                    .as_str()
+                    .chars()
+                    .filter(|c| *c != '@')
+                    .map(String::from)
+                    .collect(),
+            );
+        }
+
+        // Set transitions
+        let mut tuple_table: Vec<(usize, usize, usize)> = Vec::new();
+        let mut wildcard_table: Vec<(usize, usize)> = Vec::new();
+        let mut epsilon_increment = 1;
+        // Wildcard rows apply to every letter, and do not take a letter index.
+        let (wildcards, letters): (Vec<_>, Vec<_>) = contents
+            .next()
+            .unwrap()
+            .into_inner()
+            .partition(|a| a.as_rule() == Rule::wildcardTransitions);
+        for w in wildcards {
+            for (i_s, s_in) in w.into_inner().next().unwrap().into_inner().enumerate() {
+                for e in Automaton::parse_targets(&s_in, offset)? {
+                    wildcard_table.push((i_s + offset, e));
+                }
+            }
+        }
+        for (i_a, a) in letters.into_iter().enumerate() {
+            // Set the alphabet type
+            let i_with_eps = match alphabet_parse.as_str().chars().nth(i_a) {
+                Some('@') if alphabet_parse.as_rule() == Rule::LETTER_STR => {
+                    epsilon_increment = 0;
+                    ret.alphabet -= 1;
+                    0
                 }
+                _ => i_a + epsilon_increment,
+            };
+            if i_with_eps > ret.alphabet {
+                return Err(ParseError::at(
+                    &a,
+                    ParseErrorKind::LetterOutOfRange {
+                        letter: i_with_eps,
+                        alphabet: ret.alphabet,
+                    },
+                ));
+            }
 
-                // Set transitions
-                let mut tuple_table: Vec<(usize, usize, usize)> = Vec::new();
-                let mut epsilon_increment = 1;
-                for (i_a, a) in contents
-                    .next()
-                    .unwrap()
-                    .into_inner()
-                    .into_iter()
-                    .enumerate()
-                {
-                    // Set the alphabet type
-                    let i_with_eps = match alphabet_parse.as_rule() {
-                        Rule::LETTER_STR => match alphabet_parse.as_str().chars().nth(i_a) {
-                            Some(letter) => match letter {
-                                '@' => {
-                                    epsilon_increment = 0;
-                                    ret.alphabet = ret.alphabet - 1;
-                                    0
-                                }
-                                _ => i_a + epsilon_increment,
-                            },
-                            None => i_a + epsilon_increment,
-                        },
-                        _ => i_a + epsilon_increment,
-                    };
-
-                    // Use barebones array parsing here as it is faster than pest's parsing speeds for arrays.
-                    for (i_s, s_in) in a.into_inner().into_iter().enumerate() {
-                        for (_, s_out) in s_in
-                            .as_str()
-                            .trim_matches(|c| {
-                                c == '[' || c == ']' || c == '\n' || c == ' ' || c == '\t'
-                            })
-                            .split(',')
-                            .enumerate()
-                        {
-                            if s_out.len() > 0 {
-                                tuple_table.push((
-                                    i_s + 1,
-                                    i_with_eps,
-                                    s_out.trim().parse::<usize>().unwrap(),
-                                ));
-                            }
-                        }
-                    }
+            for (i_s, s_in) in a.into_inner().enumerate() {
+                for e in Automaton::parse_targets(&s_in, offset)? {
+                    tuple_table.push((i_s + offset, i_with_eps, e));
                 }
-                ret.table = tuple_table;
+            }
+        }
+
+        // Expand wildcards on the letters without a more specific transition.
+        let specific: HashSet<(usize, usize)> =
+            tuple_table.iter().map(|(s, a, _)| (*s, *a)).collect();
+        for (s, e) in wildcard_table {
+            (1..ret.alphabet + 1)
+                .filter(|a| !specific.contains(&(s, *a)))
+                .for_each(|a| tuple_table.push((s, a, e)));
+        }
+        ret.table = tuple_table;
 
-                // Set start states.
-                let mut start: Vec<usize> = Vec::new();
-                for num in contents.next().unwrap().into_inner() {
-                    start.push(str::parse(num.as_str().trim()).unwrap());
+        // Set start and accept states.
+        let start_parse = contents.next().unwrap();
+        ret.start = Automaton::parse_targets(&start_parse, offset)?;
+        let end_parse = contents.next().unwrap();
+        ret.end = Automaton::parse_targets(&end_parse, offset)?;
+
+        // Shift states down to be numbered from 0.
+        let referenced = ret
+            .table
+            .iter()
+            .flat_map(|(s, _, e)| [*s, *e])
+            .chain(ret.start.iter().copied())
+            .chain(ret.end.iter().copied());
+        // Grow the size to cover every state referenced.
+        ret.size = referenced
+            .map(|s| s + 1 - offset)
+            .fold(ret.size, usize::max);
+        ret.table
+            .iter_mut()
+            .for_each(|(s, _, e)| (*s, *e) = (*s - offset, *e - offset));
+        ret.start.iter_mut().for_each(|s| *s -= offset);
+        ret.end.iter_mut().for_each(|s| *s -= offset);
+        // Targets listed twice, or by both a row and a wildcard, give one transition.
+        ret.dedup_transitions();
+        Ok(ret)
+    }
+
+    /// Parse a number of the automaton format.
+    fn parse_num(pair: &Pair<Rule>) -> Result<usize, ParseError> {
+        pair.as_str().trim().parse().map_err(|_| {
+            ParseError::at(
+                pair,
+                ParseErrorKind::NumberTooLarge(pair.as_str().to_string()),
+            )
+        })
+    }
+
+    /// Parse the states of an array of the transition table, or of the start or accept states.
+    /// Returns an error at the array if a state is numbered below the offset of the index base.
+    /// Use barebones array parsing here as it is faster than pest's parsing speeds for arrays.
+    fn parse_targets(arr: &Pair<Rule>, offset: usize) -> Result<Vec<usize>, ParseError> {
+        arr.as_str()
+            .trim_matches(|c| c == '[' || c == ']' || c == '\n' || c == ' ' || c == '\t')
+            .split(',')
+            .filter(|s_out| !s_out.trim().is_empty())
+            .map(|s_out| {
+                let s = s_out.trim().parse::<usize>().map_err(|_| {
+                    ParseError::at(
+                        arr,
+                        ParseErrorKind::NumberTooLarge(s_out.trim().to_string()),
+                    )
+                })?;
+                match s < offset {
+                    true => Err(ParseError::at(arr, ParseErrorKind::StateOutOfRange(s))),
+                    false => Ok(s),
                 }
-                ret.start = start;
+            })
+            .collect()
+    }
 
-                // Set ends states.
-                let mut end: Vec<usize> = Vec::new();
-                for num in contents.next().unwrap().into_inner() {
-                    end.push(str::parse(num.as_str().trim()).unwrap());
+    /// Parse an acceptor from the OpenFST text format. Each line is either a transition
+    /// `src dst ilabel [olabel] [weight]` or an accept state `state [weight]`, and state 0 is the
+    /// start state. Output labels and weights are ignored, and input label 0 is the empty char.
+    /// The alphabet is the largest input label found.
+    /// Returns an error if a line has too many columns, or a state or input label is not a
+    /// number or is too large.
+    pub fn from_att(s: &str) -> Result<Automaton, ParseError> {
+        let mut table = vec![];
+        let mut end = vec![];
+        let mut size = 1;
+        for (i, line) in s.lines().enumerate() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // Error at a field of the line, counting columns in chars as pest does.
+            let error_at = |field: &str, kind: ParseErrorKind| {
+                let offset = field.as_ptr() as usize - line.as_ptr() as usize;
+                ParseError {
+                    line: i + 1,
+                    column: line[..offset].chars().count() + 1,
+                    kind,
                 }
-                ret.end = end;
-                ret
+            };
+            let ids = match fields.len() {
+                0 => continue,
+                1 | 2 => &fields[..1],
+                3..=5 => &fields[..3],
+                _ => {
+                    return Err(error_at(
+                        fields[5],
+                        ParseErrorKind::Syntax("Too many columns!".to_string()),
+                    ))
+                }
+            };
+            let ids = ids
+                .iter()
+                .map(|f| {
+                    f.parse::<usize>()
+                        .map_err(|_| match f.bytes().all(|b| b.is_ascii_digit()) {
+                            true => error_at(f, ParseErrorKind::NumberTooLarge(f.to_string())),
+                            false => error_at(
+                                f,
+                                ParseErrorKind::Syntax(format!("Invalid state or label {}!", f)),
+                            ),
+                        })
+                })
+                .collect::<Result<Vec<usize>, ParseError>>()?;
+            match ids[..] {
+                [state] => {
+                    size = size.max(state + 1);
+                    end.push(state);
+                }
+                [src, dst, label] => {
+                    size = size.max(src.max(dst) + 1);
+                    table.push((src, label, dst));
+                }
+                _ => unreachable!(),
             }
-            Err(error) => {
-                println!("{:?}", error.to_string());
-                Automaton::empty()
+        }
+        let alphabet = table.iter().map(|t| t.1).max().unwrap_or(0);
+        end.sort_unstable();
+        end.dedup();
+        let mut aut = Automaton::new(AutomatonType::NonDet, size, alphabet, table, vec![0], end);
+        aut.dedup_transitions();
+        Ok(aut)
+    }
+
+    /// Read an automaton from a file of the automaton format compressed with lz4, with its
+    /// uncompressed size prepended as `lz4_flex::compress_prepend_size` writes it. Parsing errors
+    /// are reported as invalid data.
+    pub fn from_compressed_file<P: AsRef<Path>>(path: P) -> io::Result<Automaton> {
+        let compressed = fs::read(path)?;
+        let bytes = decompress_size_prepended(&compressed)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+        let s = String::from_utf8(bytes).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+        Automaton::try_from(s.as_str()).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// Write the automaton and another one in the paired line-based format of Hopcroft-Karp
+    /// equivalence checkers. The shared alphabet comes first, then each automaton as a header
+    /// line, its start and accept states, and one `source letter target` line per transition:
+    ///
+    /// ```text
+    /// alphabet <letters>
+    /// automaton <states> <transitions>
+    /// start <states...>
+    /// accept <states...>
+    /// <source> <letter> <target>
+    /// ...
+    /// ```
+    pub fn to_hk_pair_format(&self, other: &Automaton) -> String {
+        let mut out = format!("alphabet {}\n", self.alphabet.max(other.alphabet));
+        for aut in [self, other] {
+            let join = |v: &Vec<usize>| v.iter().map(|s| format!(" {}", s)).collect::<String>();
+            out.push_str(&format!("automaton {} {}\n", aut.size, aut.table.len()));
+            out.push_str(&format!("start{}\n", join(&aut.start)));
+            out.push_str(&format!("accept{}\n", join(&aut.end)));
+            aut.table
+                .iter()
+                .for_each(|(s, a, e)| out.push_str(&format!("{} {} {}\n", s, a, e)));
+        }
+        out
+    }
+
+    /// Get the name of a letter from the alphabet labels, or its number if it has no label.
+    pub fn letter_label(&self, a: usize) -> String {
+        match self
+            .alphabet_labels
+            .as_ref()
+            .and_then(|l| l.get(a.wrapping_sub(1)))
+        {
+            Some(label) => label.clone(),
+            None => a.to_string(),
+        }
+    }
+
+    /// Write the automaton in the Graphviz DOT format - Accept states are double circles, each
+    /// start state has an arrow from an invisible node, and edges are labelled with the name of
+    /// their letter, or `ε` for the empty char.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph automaton {\n    rankdir=LR;\n");
+        for s in 0..self.size {
+            let shape = match self.end.contains(&s) {
+                true => "doublecircle",
+                false => "circle",
+            };
+            out.push_str(&format!("    {} [shape={}];\n", s, shape));
+        }
+        for s in &self.start {
+            out.push_str(&format!("    start{} [shape=point, style=invis];\n", s));
+            out.push_str(&format!("    start{} -> {};\n", s, s));
+        }
+        self.table
+            .iter()
+            .copied()
+            .chain(
+                self.epsilon_table
+                    .iter()
+                    .flatten()
+                    .map(|(s, e)| (*s, 0, *e)),
+            )
+            .for_each(|(s, a, e)| {
+                let label = match a {
+                    0 => "ε".to_string(),
+                    a => self.letter_label(a).replace('"', "\\\""),
+                };
+                out.push_str(&format!("    {} -> {} [label=\"{}\"];\n", s, e, label));
+            });
+        out.push_str("}\n");
+        out
+    }
+
+    /// Write the transitions of the automaton as CSV rows `from,letter,to` under a header, after a
+    /// preamble of `#` comment lines listing the start and accept states. Letters are written as
+    /// their name, quoted if needed, and empty char transitions with letter 0:
+    ///
+    /// ```text
+    /// # start: <states...>
+    /// # accept: <states...>
+    /// from,letter,to
+    /// <source>,<letter>,<target>
+    /// ...
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let join = |v: &Vec<usize>| v.iter().map(|s| format!(" {}", s)).collect::<String>();
+        let mut out = format!("# start:{}\n", join(&self.start));
+        out.push_str(&format!("# accept:{}\n", join(&self.end)));
+        out.push_str("from,letter,to\n");
+        self.table
+            .iter()
+            .copied()
+            .chain(
+                self.epsilon_table
+                    .iter()
+                    .flatten()
+                    .map(|(s, e)| (*s, 0, *e)),
+            )
+            .for_each(|(s, a, e)| {
+                let label = match a {
+                    0 => "0".to_string(),
+                    a => self.letter_label(a),
+                };
+                let label = match label.contains([',', '"', '\n']) {
+                    true => format!("\"{}\"", label.replace('"', "\"\"")),
+                    false => label,
+                };
+                out.push_str(&format!("{},{},{}\n", s, label, e))
+            });
+        out
+    }
+
+    /// Write the automaton like `Display`, with a comment line naming the role of each start,
+    /// accept and sink state - A sink state cannot reach any accept state. Comments are skipped
+    /// when parsing the output back.
+    pub fn to_annotated_nfa_string(&self) -> String {
+        let plain = self.to_string();
+        let live = self.get_live_states();
+        let comments: String = (0..self.size)
+            .filter_map(|s| {
+                let roles: Vec<&str> = [
+                    (self.start.contains(&s), "start"),
+                    (self.end.contains(&s), "accept"),
+                    (!live.contains(&s), "sink"),
+                ]
+                .into_iter()
+                .filter_map(|(has_role, role)| has_role.then_some(role))
+                .collect();
+                (!roles.is_empty()).then(|| format!("# State {}: {}\n", s + 1, roles.join(", ")))
+            })
+            .collect();
+        format!("{{\n{}{}", comments, &plain[1..])
+    }
+
+    /// Write a deterministic automaton as the DFA JSON of AALpy's state setup - States are named
+    /// `q<id>`, input symbols are letter numbers, and the initial state is the first start state:
+    ///
+    /// ```text
+    /// {"initial_state": "q0", "states": {"q0": {"accepting": false, "transitions": {"1": "q1"}}}}
+    /// ```
+    ///
+    /// Empty char transitions are left out, and only the first target of each state and letter
+    /// is written.
+    pub fn to_aalpy_json(&self) -> String {
+        let arr = self.get_transition_array();
+        let end: HashSet<usize> = self.end.iter().copied().collect();
+        let states = (0..self.size)
+            .map(|s| {
+                let transitions = (1..self.alphabet + 1)
+                    .filter_map(|a| arr[a][s].first().map(|e| format!("\"{}\": \"q{}\"", a, e)))
+                    .collect::<Vec<String>>();
+                format!(
+                    "\"q{}\": {{\"accepting\": {}, \"transitions\": {{{}}}}}",
+                    s,
+                    end.contains(&s),
+                    transitions.join(", ")
+                )
+            })
+            .collect::<Vec<String>>();
+        let initial = match self.start.first() {
+            Some(s) => format!("\"q{}\"", s),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\"initial_state\": {}, \"states\": {{{}}}}}",
+            initial,
+            states.join(", ")
+        )
+    }
+
+    /// Generate the Rust source of a function `fn fn_name(input: &[usize]) -> bool` recognizing
+    /// the words of a deterministic automaton, with a `match` over the state and letter for its
+    /// transitions. Only the first target of each state and letter is used, and words leaving
+    /// the transitions are rejected.
+    pub fn to_rust_matcher(&self, fn_name: &str) -> String {
+        let mut arms: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        self.table
+            .iter()
+            .filter(|(_, a, _)| *a != 0)
+            .for_each(|(s, a, e)| {
+                arms.entry((*s, *a)).or_insert(*e);
+            });
+        let start = match self.start.first() {
+            Some(s) => s,
+            None => {
+                return format!(
+                    "fn {}(_input: &[usize]) -> bool {{\n    false\n}}\n",
+                    fn_name
+                )
             }
         };
+        let accept = if self.end.is_empty() {
+            "false".to_string()
+        } else {
+            format!(
+                "matches!(state, {})",
+                self.end
+                    .iter()
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" | ")
+            )
+        };
+
+        let mut out = format!("fn {}(input: &[usize]) -> bool {{\n", fn_name);
+        out.push_str(&format!("    let mut state: usize = {};\n", start));
+        out.push_str("    for letter in input {\n        state = match (state, *letter) {\n");
+        arms.iter().for_each(|((s, a), e)| {
+            out.push_str(&format!("            ({}, {}) => {},\n", s, a, e))
+        });
+        out.push_str("            _ => return false,\n        };\n    }\n");
+        out.push_str(&format!("    {}\n}}\n", accept));
+        out
+    }
+
+    /// Get the edges of the automaton between each pair of states, with the letters of each edge
+    /// merged into ranges of consecutive letters `(first, last)`. Edges are sorted by states.
+    pub fn merged_edge_labels(&self) -> Vec<MergedEdge> {
+        let mut edges: BTreeMap<(usize, usize), BTreeSet<usize>> = BTreeMap::new();
+        self.table.iter().for_each(|(s, a, e)| {
+            edges.entry((*s, *e)).or_default().insert(*a);
+        });
+        edges
+            .into_iter()
+            .map(|((s, e), letters)| {
+                let mut ranges: Vec<(usize, usize)> = Vec::new();
+                for a in letters {
+                    match ranges.last_mut() {
+                        Some((_, last)) if *last + 1 == a => *last = a,
+                        _ => ranges.push((a, a)),
+                    }
+                }
+                (s, e, ranges)
+            })
+            .collect()
+    }
+
+    /// Write the automaton like `Display`, with its empty char transitions. Returns an error for
+    /// automata with empty char transitions and more than 52 letters without single letter
+    /// labels, whose alphabet cannot be written as letters.
+    pub fn to_nfa_string(&self) -> Result<String, String> {
+        match self.nfa_alphabet() {
+            Some(alphabet) => Ok(self.format_nfa(&alphabet)),
+            None => Err(format!(
+                "Automata with empty char transitions are limited to 52 letters, not {}!",
+                self.alphabet
+            )),
+        }
+    }
+
+    /// Get the alphabet as written in the automaton format, or None if the automaton has empty
+    /// char transitions and its alphabet cannot be written as letters.
+    fn nfa_alphabet(&self) -> Option<String> {
+        let has_epsilon = self.has_epsilon();
+        let labels = self
+            .alphabet_labels
+            .as_ref()
+            .filter(|labels| {
+                labels.len() == self.alphabet
+                    && labels
+                        .iter()
+                        .all(|l| l.len() == 1 && l.chars().all(|c| c.is_ascii_alphabetic()))
+            })
+            .map(|labels| labels.concat());
+        match (labels, has_epsilon) {
+            (Some(labels), true) => Some(format!("\"{}@\"", labels)),
+            (Some(labels), false) => Some(format!("\"{}\"", labels)),
+            (None, true) => LETTERS
+                .get(..self.alphabet)
+                .map(|letters| format!("\"{}@\"", letters)),
+            (None, false) => Some(self.alphabet.to_string()),
+        }
+    }
+
+    /// Write the automaton in the automaton format with the given alphabet.
+    fn format_nfa(&self, alphabet: &str) -> String {
+        let automaton_type = match self.automaton_type {
+            AutomatonType::Det => "det",
+            AutomatonType::NonDet => "nondet",
+        };
+
+        let join_states = |states: &[usize]| -> String {
+            states
+                .iter()
+                .map(|s| (s + 1).to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        let transition_arr = self.get_transition_array();
+        let transitions = (1..self.alphabet + 1)
+            .chain(self.has_epsilon().then_some(0))
+            .map(|a| {
+                let rows = (0..self.size)
+                    .map(|s| format!("[{}]", join_states(&transition_arr[a][s])))
+                    .collect::<Vec<String>>();
+                format!("[{}]", rows.join(", "))
+            })
+            .collect::<Vec<String>>();
+
+        format!(
+            "{{\"{}\", {}, {}, [{}], [{}], [{}]}}",
+            automaton_type,
+            self.size,
+            alphabet,
+            transitions.join(", "),
+            join_states(&self.start),
+            join_states(&self.end)
+        )
+    }
+
+    /// Get the automaton over the same states and language without empty char transitions -
+    /// Each state takes the transitions of the states it reaches by empty char transitions, and
+    /// accepts if one of them does.
+    fn without_epsilon(&self) -> Automaton {
+        let arr = self.get_transition_array();
+        let closures: Vec<Vec<usize>> = (0..self.size)
+            .map(|s| {
+                let mut closure = Ubig::new();
+                self.add_state(&arr, &mut closure, s);
+                closure.get_seq()
+            })
+            .collect();
+        let end: HashSet<usize> = self.end.iter().copied().collect();
+        let mut table: BTreeSet<(usize, usize, usize)> = BTreeSet::new();
+        for (s, closure) in closures.iter().enumerate() {
+            for q in closure {
+                for (a, letter_arr) in arr.iter().enumerate().skip(1) {
+                    letter_arr[*q].iter().for_each(|e| {
+                        table.insert((s, a, *e));
+                    });
+                }
+            }
+        }
+        let mut ret = Automaton::new(
+            AutomatonType::NonDet,
+            self.size,
+            self.alphabet,
+            table.into_iter().collect(),
+            self.start.clone(),
+            (0..self.size)
+                .filter(|s| closures[*s].iter().any(|q| end.contains(q)))
+                .collect(),
+        );
+        ret.alphabet_labels = self.alphabet_labels.clone();
+        ret
+    }
+}
+
+impl fmt::Display for Automaton {
+    /// Write the automaton in the format read by `Automaton::from`, with states numbered from 1.
+    /// Automata with empty char transitions write their alphabet as letters followed by `@`.
+    /// Past 52 letters, the automaton with its empty char transitions removed is written instead.
+    /// Alphabet labels of a single ASCII letter each are written as the alphabet string, and other
+    /// labels are left out.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.nfa_alphabet() {
+            Some(alphabet) => f.write_str(&self.format_nfa(&alphabet)),
+            None => write!(f, "{}", self.without_epsilon()),
+        }
     }
 }
@@ -0,0 +1,556 @@
+#[cfg(test)]
+mod tests {
+    use lz4_flex::compress_prepend_size;
+    use proptest::prelude::*;
+
+    use crate::automaton::{AlgorithmKind, Automaton, AutomatonType};
+    use crate::automaton_encoder::{IndexBase, ParseError, ParseErrorKind};
+    use crate::transition_graphs::get_random_nfa;
+
+    fn assert_round_trip(aut: &Automaton) {
+        let parsed = Automaton::from(&aut.to_string()).order_transitions();
+        // Repeated transitions are read back once.
+        let mut expected = aut.clone();
+        expected.dedup_transitions();
+        let expected = expected.order_transitions();
+        assert_eq!(parsed.automaton_type, expected.automaton_type);
+        assert_eq!(parsed.size, expected.size);
+        assert_eq!(parsed.alphabet, expected.alphabet);
+        assert_eq!(parsed.table, expected.table);
+        assert_eq!(parsed.start, expected.start);
+        assert_eq!(parsed.end, expected.end);
+    }
+
+    #[test]
+    // Test the display of a small DFA in the automaton file format.
+    fn test_display_det() {
+        let bipartite_small = Automaton::new(
+            AutomatonType::Det,
+            2,
+            2,
+            vec![(0, 1, 1), (0, 2, 1), (1, 1, 1), (1, 2, 1)],
+            vec![0],
+            vec![1],
+        );
+        assert_eq!(
+            bipartite_small.to_string(),
+            "{\"det\", 2, 2, [[[2], [2]], [[2], [2]]], [1], [2]}"
+        );
+        assert_round_trip(&bipartite_small);
+    }
+
+    #[test]
+    // Test whether empty char transitions are displayed as the `@` letter.
+    fn test_display_empty_char() {
+        let empty_char_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            1,
+            vec![(0, 0, 1), (1, 1, 1)],
+            vec![0],
+            vec![1],
+        );
+        assert_eq!(
+            empty_char_nd.to_string(),
+            "{\"nondet\", 2, \"a@\", [[[], [2]], [[2], []]], [1], [2]}"
+        );
+        assert_round_trip(&empty_char_nd);
+    }
+
+    #[test]
+    // Test whether automata without states or letters survive a round trip.
+    fn test_display_empty() {
+        assert_round_trip(&Automaton::new(
+            AutomatonType::Det,
+            0,
+            0,
+            vec![],
+            vec![],
+            vec![],
+        ));
+        assert_round_trip(&Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            0,
+            vec![],
+            vec![0],
+            vec![1],
+        ));
+    }
+
+    #[test]
+    // Test whether an lz4 compressed automaton file reads back as the uncompressed automaton.
+    fn test_from_compressed_file() {
+        let aut = get_random_nfa(6, 3, 12, 11);
+        let path =
+            std::env::temp_dir().join(format!("nfdeterminize-{}.nfa.lz4", std::process::id()));
+        std::fs::write(&path, compress_prepend_size(aut.to_string().as_bytes())).unwrap();
+        let read = Automaton::from_compressed_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let read = read.unwrap().order_transitions();
+        let parsed = Automaton::from(&aut.to_string()).order_transitions();
+        assert_eq!(read.size, parsed.size);
+        assert_eq!(read.table, parsed.table);
+        assert_eq!(read.start, parsed.start);
+        assert_eq!(read.end, parsed.end);
+        assert!(
+            Automaton::from_compressed_file(std::env::temp_dir().join("missing.nfa.lz4")).is_err()
+        );
+    }
+
+    #[test]
+    // Test whether consecutive letters between the same states are merged into ranges.
+    fn test_merged_edge_labels() {
+        let wide_d = Automaton::new(
+            AutomatonType::Det,
+            2,
+            6,
+            vec![
+                (0, 1, 1),
+                (0, 2, 1),
+                (0, 3, 1),
+                (0, 4, 1),
+                (0, 5, 0),
+                (0, 6, 1),
+                (1, 0, 0),
+            ],
+            vec![0],
+            vec![1],
+        );
+        assert_eq!(
+            wide_d.merged_edge_labels(),
+            vec![
+                (0, 0, vec![(5, 5)]),
+                (0, 1, vec![(1, 4), (6, 6)]),
+                (1, 0, vec![(0, 0)]),
+            ]
+        );
+    }
+
+    #[test]
+    // Test whether the Hopcroft-Karp pair format holds both automata with their transition counts.
+    fn test_to_hk_pair_format() {
+        let sinkhole_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 1, 2)],
+            vec![0],
+            vec![2],
+        );
+        let sinkhole_d = sinkhole_nd.determinized(AlgorithmKind::Sequential);
+        let pair = sinkhole_nd.to_hk_pair_format(&sinkhole_d);
+        let lines: Vec<&str> = pair.lines().collect();
+        assert_eq!(lines[0], "alphabet 2");
+        assert_eq!(lines[1], "automaton 3 2");
+        assert_eq!(lines[2], "start 0");
+        assert_eq!(lines[3], "accept 2");
+        assert_eq!(lines[4], "0 1 1");
+        assert!(lines.contains(&"automaton 4 8"));
+        assert_eq!(lines.len(), 1 + 3 + 2 + 3 + 8);
+    }
+
+    #[test]
+    // Test whether a wildcard row expands to every letter without a more specific transition.
+    fn test_parse_wildcard() {
+        let aut = Automaton::from(
+            &"{\"nondet\", 3, 2, [[[2], [], []], [[], [], []], *[[3], [3], []]], [1], [3]}"
+                .to_string(),
+        )
+        .order_transitions();
+        assert_eq!(aut.alphabet, 2);
+        assert_eq!(aut.table, vec![(0, 1, 1), (0, 2, 2), (1, 1, 2), (1, 2, 2)]);
+
+        let eps = Automaton::from(
+            &"{\"epsilon\", 2, \"a@\", [[[], []], [[2], []], *[[1], []]], [1], [2]}".to_string(),
+        )
+        .order_transitions();
+        assert_eq!(eps.alphabet, 1);
+        assert_eq!(eps.table, vec![(0, 0, 1), (0, 1, 0)]);
+    }
+
+    #[test]
+    // Test whether targets listed twice, empty char ones included, give a single transition.
+    fn test_parse_dedups_transitions() {
+        let aut = Automaton::from(
+            &"{\"epsilon\", 2, \"a@\", [[[2, 2], []], [[2, 1, 2], [2, 2]]], [1], [2]}".to_string(),
+        );
+        assert_eq!(aut.table, vec![(0, 1, 1), (0, 0, 1), (0, 0, 0), (1, 0, 1)]);
+    }
+
+    #[test]
+    // Test whether a transition to a state beyond the declared size grows the size.
+    fn test_parse_grows_size() {
+        let aut = Automaton::from(&"{\"nondet\", 2, 1, [[[3], [], []]], [1], [3]}".to_string());
+        assert_eq!(aut.size, 3);
+        assert_eq!(aut.table, vec![(0, 1, 2)]);
+        assert!(aut.accepts(&[1]));
+        assert_eq!(aut.determinized(AlgorithmKind::Sequential).size, 3);
+
+        let declared = Automaton::from(&"{\"nondet\", 4, 1, [[[2], []]], [1], [2]}".to_string());
+        assert_eq!(declared.size, 4);
+    }
+
+    #[test]
+    // Test whether the same automaton is read from its 0-based and 1-based files, and states
+    // below the index base are rejected.
+    fn test_parse_index_base() {
+        let one_based = "{\"nondet\", 3, 2, [[[2], [3], []], [[], [], [3]]], [1], [3]}";
+        let zero_based = "{\"nondet\", 3, 2, [[[1], [2], []], [[], [], [2]]], [0], [2]}";
+        let expected = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 1, 2), (2, 2, 2)],
+            vec![0],
+            vec![2],
+        );
+        for aut in [
+            Automaton::parse_with_base(one_based, IndexBase::One),
+            Automaton::from(&one_based.to_string()),
+            Automaton::parse_with_base(zero_based, IndexBase::Zero),
+        ] {
+            let aut = aut.order_transitions();
+            assert_eq!(aut.size, expected.size);
+            assert_eq!(aut.table, expected.table);
+            assert_eq!(aut.start, expected.start);
+            assert_eq!(aut.end, expected.end);
+        }
+
+        let rejected = Automaton::parse_with_base(zero_based, IndexBase::One);
+        assert_eq!(rejected.size, 0);
+        assert!(rejected.table.is_empty());
+    }
+
+    #[test]
+    // Test whether malformed automata give errors located where parsing failed.
+    fn test_parse_errors() {
+        let syntax = Automaton::try_from("{\"nondet\", 2, 1,\n [[[2], [x]]], [1], [2]}");
+        assert!(matches!(
+            syntax,
+            Err(ParseError {
+                line: 2,
+                kind: ParseErrorKind::Syntax(_),
+                ..
+            })
+        ));
+
+        let letters = Automaton::try_from("{\"nondet\", 2, 1, [[[2], []], [[1], []]], [1], [2]}");
+        assert_eq!(
+            letters.unwrap_err(),
+            ParseError {
+                line: 1,
+                column: 30,
+                kind: ParseErrorKind::LetterOutOfRange {
+                    letter: 2,
+                    alphabet: 1
+                },
+            }
+        );
+
+        let below_base = Automaton::try_from("{\"nondet\", 2, 1, [[[2], []]], [0], [2]}");
+        assert_eq!(
+            below_base.unwrap_err(),
+            ParseError {
+                line: 1,
+                column: 31,
+                kind: ParseErrorKind::StateOutOfRange(0)
+            }
+        );
+        // Out of range states are located at the row or accept list holding them.
+        let row_below_base = Automaton::try_from("{\"nondet\", 2, 1, [[[2],\n [0]]], [1], [2]}");
+        assert_eq!(
+            row_below_base.unwrap_err(),
+            ParseError {
+                line: 2,
+                column: 2,
+                kind: ParseErrorKind::StateOutOfRange(0)
+            }
+        );
+        let end_below_base = Automaton::try_from("{\"nondet\", 2, 1, [[[2], []]], [1],\n[0]}");
+        assert_eq!(
+            end_below_base.unwrap_err(),
+            ParseError {
+                line: 2,
+                column: 1,
+                kind: ParseErrorKind::StateOutOfRange(0)
+            }
+        );
+
+        let too_large =
+            Automaton::try_from("{\"nondet\", 99999999999999999999999, 1, [], [1], [1]}");
+        let error = too_large.unwrap_err();
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::NumberTooLarge("99999999999999999999999".to_string())
+        );
+        assert_eq!(
+            error.to_string(),
+            "Line 1, column 12: Number 99999999999999999999999 is too large!"
+        );
+
+        let valid = Automaton::try_from("{\"nondet\", 2, 1, [[[2], []]], [1], [2]}").unwrap();
+        assert_eq!(valid.table, vec![(0, 1, 1)]);
+    }
+
+    proptest! {
+        #[test]
+        // Test whether random NFAs survive a round trip through their display.
+        fn test_display_round_trip(
+            size in 0usize..12,
+            alphabet in 0usize..6,
+            transitions in 0usize..40,
+            seed in any::<u64>(),
+        ) {
+            assert_round_trip(&get_random_nfa(size, alphabet, transitions, seed));
+        }
+    }
+
+    #[test]
+    // Test whether the annotated output names state roles and parses back like the plain output.
+    fn test_annotated_nfa_string() {
+        let sinkhole_d = Automaton::new(
+            AutomatonType::Det,
+            3,
+            2,
+            vec![
+                (0, 1, 1),
+                (0, 2, 2),
+                (1, 1, 2),
+                (1, 2, 2),
+                (2, 1, 2),
+                (2, 2, 2),
+            ],
+            vec![0],
+            vec![1],
+        );
+        let annotated = sinkhole_d.to_annotated_nfa_string();
+        assert!(annotated.starts_with("{\n# State 1: start\n# State 2: accept\n# State 3: sink\n"));
+
+        let plain = Automaton::from(&sinkhole_d.to_string());
+        let reparsed = Automaton::from(&annotated);
+        assert_eq!(reparsed.size, plain.size);
+        assert_eq!(reparsed.alphabet, plain.alphabet);
+        assert_eq!(reparsed.table, plain.table);
+        assert_eq!(reparsed.start, plain.start);
+        assert_eq!(reparsed.end, plain.end);
+    }
+
+    #[test]
+    // Test whether the AALpy JSON parses with every state and the initial state.
+    fn test_aalpy_json() {
+        let sinkhole_d = Automaton::new(
+            AutomatonType::Det,
+            3,
+            2,
+            vec![
+                (0, 1, 1),
+                (0, 2, 2),
+                (1, 1, 2),
+                (1, 2, 2),
+                (2, 1, 2),
+                (2, 2, 2),
+            ],
+            vec![0],
+            vec![1],
+        );
+        let json: serde_json::Value = serde_json::from_str(&sinkhole_d.to_aalpy_json()).unwrap();
+        assert_eq!(json["initial_state"], "q0");
+        assert_eq!(json["states"].as_object().unwrap().len(), 3);
+        assert_eq!(json["states"]["q1"]["accepting"], true);
+        assert_eq!(json["states"]["q0"]["transitions"]["2"], "q2");
+    }
+
+    #[test]
+    // Test whether the generated Rust matcher encodes every transition of the sinkhole DFA.
+    fn test_rust_matcher() {
+        let sinkhole_d = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 1, 2)],
+            vec![0],
+            vec![2],
+        )
+        .determinized(AlgorithmKind::Sequential);
+        let source = sinkhole_d.to_rust_matcher("is_aa");
+        assert!(source.starts_with("fn is_aa(input: &[usize]) -> bool {\n"));
+        assert!(source.contains("    let mut state: usize = 0;\n"));
+        for (s, a, e) in &sinkhole_d.table {
+            assert!(source.contains(&format!("            ({}, {}) => {},\n", s, a, e)));
+        }
+        assert!(source.contains("            _ => return false,\n"));
+        assert!(source.ends_with("    matches!(state, 3)\n}\n"));
+    }
+
+    #[test]
+    // Test whether an OpenFST acceptor with an empty char, output labels and weights is
+    // imported and determinized to accept `1 2*`.
+    fn test_from_att() {
+        let att = "0 1 1 1 0.5\n1 2 0 0\n2 2 2 2 1.0\n\n2 0.25\n";
+        let aut = Automaton::from_att(att).unwrap();
+        assert_eq!(aut.size, 3);
+        assert_eq!(aut.alphabet, 2);
+        assert_eq!(aut.table, vec![(0, 1, 1), (1, 0, 2), (2, 2, 2)]);
+        assert_eq!(aut.start, vec![0]);
+        assert_eq!(aut.end, vec![2]);
+
+        let dfa = aut.determinized(AlgorithmKind::Sequential);
+        assert!(dfa.is_deterministic());
+        assert!(dfa.accepts(&[1]));
+        assert!(dfa.accepts(&[1, 2, 2]));
+        assert!(!dfa.accepts(&[]));
+        assert!(!dfa.accepts(&[2, 1]));
+
+        let error = Automaton::from_att("0 1 1\n0 x 1").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert!(matches!(error.kind, ParseErrorKind::Syntax(_)));
+        let error = Automaton::from_att("0 1 1 1 1  1").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 12));
+        let error = Automaton::from_att("0 1 99999999999999999999999").unwrap_err();
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::NumberTooLarge("99999999999999999999999".to_string())
+        );
+    }
+
+    #[test]
+    // Test whether the CSV export lists the start and accept states, and one row per transition.
+    fn test_to_csv() {
+        let sinkhole_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 1, 2), (1, 2, 2)],
+            vec![0],
+            vec![1, 2],
+        );
+        let csv = sinkhole_nd.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[..3],
+            ["# start: 0", "# accept: 1 2", "from,letter,to"]
+        );
+        assert_eq!(lines[3..], ["0,1,1", "1,1,2", "1,2,2"]);
+    }
+
+    #[test]
+    // Test whether the DOT export marks accept states, start arrows and letter labels, with the
+    // empty char written as epsilon.
+    fn test_to_dot() {
+        let empty_char_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 0, 2), (2, 2, 2)],
+            vec![0],
+            vec![2],
+        );
+        let dot = empty_char_nd.to_dot();
+        assert!(dot.starts_with("digraph automaton {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("    0 [shape=circle];\n"));
+        assert!(dot.contains("    2 [shape=doublecircle];\n"));
+        assert!(dot.contains("    start0 -> 0;\n"));
+        assert!(dot.contains("    0 -> 1 [label=\"1\"];\n"));
+        assert!(dot.contains("    1 -> 2 [label=\"ε\"];\n"));
+        assert!(dot.contains("    2 -> 2 [label=\"2\"];\n"));
+        assert_eq!(dot.matches(" -> ").count(), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    // Test whether an automaton with every optional field set survives a JSON round trip.
+    fn test_serde_json_round_trip() {
+        let mut aut = get_random_nfa(6, 2, 12, 3);
+        aut.accept_priority = Some((0..aut.end.len()).collect());
+        aut.probabilities = Some(vec![0.5; aut.table.len()]);
+        aut.epsilon_table = Some(vec![(0, 1), (2, 3)]);
+        aut.alphabet_labels = Some(vec!["a".to_string(), "0x1f".to_string()]);
+
+        let json = serde_json::to_string(&aut).unwrap();
+        let parsed: Automaton = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.automaton_type, aut.automaton_type);
+        assert_eq!(parsed.size, aut.size);
+        assert_eq!(parsed.alphabet, aut.alphabet);
+        assert_eq!(parsed.table, aut.table);
+        assert_eq!(parsed.start, aut.start);
+        assert_eq!(parsed.end, aut.end);
+        assert_eq!(parsed.accept_priority, aut.accept_priority);
+        assert_eq!(parsed.probabilities, aut.probabilities);
+        assert_eq!(parsed.epsilon_table, aut.epsilon_table);
+        assert_eq!(parsed.alphabet_labels, aut.alphabet_labels);
+    }
+
+    #[test]
+    // Test whether letters written as a string name the letters, survive determinization,
+    // minimization and a round trip through the format, and name the letters of DOT and CSV
+    // output.
+    fn test_alphabet_labels() {
+        let parsed = Automaton::from(
+            &"{\"nondet\", 2, \"xy@\", [[[1, 2], []], [[], [2]], [[2], []]], [1], [2]}".to_string(),
+        );
+        assert_eq!(
+            parsed.alphabet_labels,
+            Some(vec!["x".to_string(), "y".to_string()])
+        );
+        assert_eq!(parsed.letter_label(2), "y");
+        assert_eq!(parsed.letter_label(3), "3");
+        assert!(parsed.to_string().contains("\"xy@\""));
+        assert_eq!(
+            Automaton::from(&parsed.to_string()).alphabet_labels,
+            parsed.alphabet_labels
+        );
+
+        let dfa = parsed.determinized(AlgorithmKind::Sequential);
+        assert_eq!(dfa.alphabet_labels, parsed.alphabet_labels);
+        assert_eq!(dfa.minimized().alphabet_labels, parsed.alphabet_labels);
+        assert!(dfa.to_dot().contains("[label=\"x\"]"));
+        assert!(!dfa.to_dot().contains("[label=\"1\"]"));
+
+        let mut tokens = Automaton::new(
+            AutomatonType::Det,
+            1,
+            2,
+            vec![(0, 1, 0), (0, 2, 0)],
+            vec![0],
+            vec![0],
+        );
+        tokens.alphabet_labels = Some(vec!["0x1f".to_string(), "a,b".to_string()]);
+        let csv = tokens.to_csv();
+        assert!(csv.contains("0,0x1f,0\n"));
+        assert!(csv.contains("0,\"a,b\",0\n"));
+        // Labels that cannot be written as the alphabet string are left out.
+        assert!(tokens.to_string().contains(", 2, ["));
+        assert_eq!(
+            Automaton::from(&"{\"det\", 1, 2, [[[1]], [[1]]], [1], [1]}".to_string())
+                .alphabet_labels,
+            None
+        );
+    }
+
+    #[test]
+    // Test whether automata with empty char transitions and more than 52 letters get written
+    // without them rather than failing, and are rejected by the writer keeping them.
+    fn test_display_many_letters_epsilon() {
+        let mut many_letters = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            53,
+            vec![(0, 53, 1), (2, 1, 2)],
+            vec![0],
+            vec![2],
+        );
+        many_letters.epsilon_table = Some(vec![(1, 2)]);
+        assert!(many_letters.to_nfa_string().is_err());
+
+        let parsed = Automaton::from(&many_letters.to_string());
+        assert!(!parsed.has_epsilon());
+        assert_eq!(parsed.alphabet, 53);
+        assert!(parsed.equivalent(&many_letters));
+        assert!(many_letters
+            .to_annotated_nfa_string()
+            .contains("# State 1: start"));
+    }
+}
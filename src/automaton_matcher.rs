@@ -0,0 +1,65 @@
+use std::collections::HashSet;
+
+use crate::{automaton::Automaton, ubig::Ubig};
+
+/// Status of a matcher after reading a letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchStatus {
+    /// Whether the letters read so far form an accepted word.
+    pub accepting: bool,
+    /// Whether reading more letters can still lead to an accepted word.
+    pub can_continue: bool,
+}
+
+/// Streaming matcher reading a word one letter at a time, keeping the set of states reached
+/// closed over empty char transitions.
+pub struct Matcher<'a> {
+    aut: &'a Automaton,
+    transition_arr: Vec<Vec<Vec<usize>>>,
+    live: HashSet<usize>,
+    set: Ubig,
+}
+
+impl<'a> Matcher<'a> {
+    /// Create a matcher at the start states of an automaton.
+    pub fn new(aut: &'a Automaton) -> Matcher<'a> {
+        let transition_arr = aut.get_transition_array();
+        let set = aut.get_start_set(&transition_arr);
+        Matcher {
+            aut,
+            transition_arr,
+            live: aut.get_live_states(),
+            set,
+        }
+    }
+
+    /// Get the status of the matcher for the letters read so far.
+    pub fn status(&self) -> MatchStatus {
+        let states = self.set.get_seq();
+        MatchStatus {
+            accepting: self.aut.end.iter().any(|s| self.set.bit_at(s)),
+            can_continue: states.iter().any(|s| {
+                self.transition_arr
+                    .iter()
+                    .skip(1)
+                    .any(|letter_arr| letter_arr[*s].iter().any(|e| self.live.contains(e)))
+            }),
+        }
+    }
+
+    /// Read a letter, and get the status of the matcher after it. Letters outside of the
+    /// alphabet leave no state reached.
+    pub fn advance(&mut self, letter: usize) -> MatchStatus {
+        self.set = self
+            .aut
+            .get_next_set(&self.transition_arr, &self.set, letter);
+        self.status()
+    }
+}
+
+impl Automaton {
+    /// Return a streaming matcher at the start states of the automaton.
+    pub fn matcher(&self) -> Matcher<'_> {
+        Matcher::new(self)
+    }
+}
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use crate::automaton::{Automaton, AutomatonType};
+    use crate::automaton_matcher::MatchStatus;
+
+    #[test]
+    // Test the status of a matcher of a·b*·c after each letter of a word.
+    fn test_matcher_advance() {
+        let abc_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            3,
+            vec![(0, 1, 1), (1, 0, 2), (2, 2, 2), (2, 3, 3)],
+            vec![0],
+            vec![3],
+        );
+        let mut matcher = abc_nd.matcher();
+        let status = |accepting, can_continue| MatchStatus {
+            accepting,
+            can_continue,
+        };
+        assert_eq!(matcher.status(), status(false, true));
+        assert_eq!(matcher.advance(1), status(false, true));
+        assert_eq!(matcher.advance(2), status(false, true));
+        assert_eq!(matcher.advance(3), status(true, false));
+        assert_eq!(matcher.advance(2), status(false, false));
+    }
+}
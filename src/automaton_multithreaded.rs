@@ -1,23 +1,24 @@
 use fasthash::xx::{self, Hasher64};
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     hash::{BuildHasherDefault, Hasher},
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
         mpsc::{channel, Receiver, Sender},
         Arc, Mutex,
     },
     thread,
 };
-use uuid::Uuid;
 
-use crate::{
-    automaton::Automaton,
-    ubig::{CompressedUbig, Ubig},
-};
+use crate::{automaton::Automaton, state_set::StateSet};
 
 type HashMapXX<K, V> = HashMap<K, V, BuildHasherDefault<Hasher64>>;
 type Transition = (usize, usize, usize);
+/// Map from the sets of states owned by a thread to their DFA state ids.
+type SharedNumMap<S> = Arc<Mutex<HashMapXX<<S as StateSet>::Key, usize>>>;
+
+/// Number of letters a worker thread computes successor sets for at once.
+const LETTER_BATCH_SIZE: usize = 16;
 
 ////////////////
 // Algorithms //
@@ -25,15 +26,20 @@ type Transition = (usize, usize, usize);
 
 /// Struct of variables used for each
 /// superset construction worker thread
-struct RabinScottWorkerThreadMembers<'a> {
+struct RabinScottWorkerThreadMembers<'a, S: StateSet> {
     aut: &'a Automaton,
     i: usize,
     n_threads: usize,
     transition_arr: Vec<Vec<Vec<usize>>>,
     end: HashSet<usize>,
     stop_sig: Arc<AtomicBool>,
-    num_maps: Vec<Arc<Mutex<HashMapXX<CompressedUbig, usize>>>>,
-    frontiers: Vec<Arc<Mutex<VecDeque<Ubig>>>>,
+    next_id: Arc<AtomicUsize>,
+    num_maps: Vec<SharedNumMap<S>>,
+    frontiers: Vec<Arc<Mutex<VecDeque<S>>>>,
+    /// Whether each worker is declared idle to the main thread. Only changed while holding the
+    /// lock of that worker's frontier, or by the worker itself while holding the lock of the
+    /// frontier it steals from.
+    idle: Arc<Vec<AtomicBool>>,
     frontier_empty_tx: Sender<(bool, usize)>,
     reduce_tx: Sender<usize>,
     transition_tx: Sender<Transition>,
@@ -41,20 +47,21 @@ struct RabinScottWorkerThreadMembers<'a> {
 }
 
 /// Multithreaded version of the Rabin-Scott/superset construction algorithm.
-pub fn rabin_scott_mt(
+pub fn rabin_scott_mt<S: StateSet>(
     aut: &Automaton,
     n_threads: usize,
 ) -> (Vec<Transition>, usize, Vec<usize>, Vec<usize>) {
     // Shared Memory in the algorithm
     let mut transitions: Vec<Transition> = Vec::new();
     let mut accept_states: Vec<usize> = Vec::new();
-    let mut id_state_map: HashMapXX<usize, usize> = HashMapXX::default();
+    // States are numbered densely in the order they are discovered, the start state being 0.
+    let next_id = Arc::new(AtomicUsize::new(1));
 
     // Variables belonging to threads
-    let num_maps: Vec<Arc<Mutex<HashMapXX<CompressedUbig, usize>>>> = (0..n_threads)
+    let num_maps: Vec<SharedNumMap<S>> = (0..n_threads)
         .map(|_| Arc::new(Mutex::new(HashMapXX::default())))
         .collect();
-    let frontier_c: Vec<Arc<Mutex<VecDeque<Ubig>>>> = (0..n_threads)
+    let frontier_c: Vec<Arc<Mutex<VecDeque<S>>>> = (0..n_threads)
         .map(|_| Arc::new(Mutex::new(VecDeque::new())))
         .collect();
     let (frontier_empty_tx, frontier_empty_rx): (Sender<(bool, usize)>, Receiver<(bool, usize)>) =
@@ -65,7 +72,7 @@ pub fn rabin_scott_mt(
 
     // Select start state from all start states in the non deterministic automata.
     let transition_arr = aut.get_transition_array();
-    let mut start_state = Ubig::new();
+    let mut start_state = S::default();
     (&aut.start)
         .into_iter()
         .for_each(|s| aut.add_state(&transition_arr, &mut start_state, *s));
@@ -76,11 +83,10 @@ pub fn rabin_scott_mt(
         }
     }
     let start_hash = get_hash(&start_state, n_threads);
-    id_state_map.insert(0, 0);
     num_maps[start_hash]
         .lock()
         .unwrap()
-        .insert(start_state.clone().compress(), 0);
+        .insert(start_state.to_key(), 0);
     frontier_c[start_hash]
         .lock()
         .unwrap()
@@ -88,6 +94,8 @@ pub fn rabin_scott_mt(
 
     thread::scope(|s| {
         let stop_sig: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let idle: Arc<Vec<AtomicBool>> =
+            Arc::new((0..n_threads).map(|_| AtomicBool::new(false)).collect());
 
         // Initialise worker thread vars and spawn worker threads
         for i in 0..n_threads {
@@ -98,8 +106,10 @@ pub fn rabin_scott_mt(
                 n_threads,
                 end: aut.end.iter().map(|i| *i).collect(),
                 stop_sig: Arc::clone(&stop_sig),
+                next_id: Arc::clone(&next_id),
                 num_maps: num_maps.iter().map(|a| Arc::clone(a)).collect(),
                 frontiers: frontier_c.iter().map(|a| Arc::clone(a)).collect(),
+                idle: Arc::clone(&idle),
                 transition_tx: transition_tx.clone(),
                 reduce_tx: reduce_tx.clone(),
                 accept_tx: accept_tx.clone(),
@@ -126,10 +136,10 @@ pub fn rabin_scott_mt(
         let mut threads_reduces = (0..n_threads).map(|_| false).collect::<Vec<bool>>();
         while count > 0 {
             if let Ok(tr) = transition_rx.try_recv() {
-                add_transition(tr, &mut transitions, &mut id_state_map);
+                transitions.push(tr);
             }
             if let Ok(s) = accept_rx.try_recv() {
-                add_accept(s, &mut accept_states, &mut id_state_map);
+                accept_states.push(s);
             }
             if let Ok(thread) = reduce_rx.try_recv() {
                 if !threads_reduces[thread] {
@@ -138,14 +148,11 @@ pub fn rabin_scott_mt(
                 }
             }
         }
-        transition_rx
-            .try_iter()
-            .for_each(|tr| add_transition(tr, &mut transitions, &mut id_state_map));
-        accept_rx
-            .try_iter()
-            .for_each(|s| add_accept(s, &mut accept_states, &mut id_state_map));
+        transitions.extend(transition_rx.try_iter());
+        accept_states.extend(accept_rx.try_iter());
     });
-    return (transitions, id_state_map.len(), vec![0], accept_states);
+    let size = next_id.load(Ordering::SeqCst);
+    (transitions, size, vec![0], accept_states)
 }
 
 ////////////////////
@@ -153,30 +160,46 @@ pub fn rabin_scott_mt(
 ////////////////////
 
 /// Worker thread behaviour during superset construction
-fn rabin_scott_worker_mt(tm: RabinScottWorkerThreadMembers) {
+fn rabin_scott_worker_mt<S: StateSet>(tm: RabinScottWorkerThreadMembers<S>) {
     let mut local_transitions: Vec<Transition> = Vec::new();
     let mut local_accepts: Vec<usize> = Vec::new();
-    let mut frontier_empty = false;
+    let mut pool: Vec<S> = (0..LETTER_BATCH_SIZE).map(|_| S::default()).collect();
     loop {
-        let next: Option<Ubig>;
+        let next: Option<S>;
         let mut f = tm.frontiers[tm.i].lock().unwrap();
         next = f.pop_front();
         if let Some(next) = next {
-            if frontier_empty {
+            if tm.idle[tm.i].swap(false, Ordering::SeqCst) {
                 tm.frontier_empty_tx.send((false, tm.i)).unwrap();
-                frontier_empty = false;
             }
             drop(f);
             rabin_scott_worker_mt_explore_loop(
                 &tm,
                 next,
+                &mut pool,
                 &mut local_transitions,
                 &mut local_accepts,
             );
-        } else if !frontier_empty {
-            frontier_empty = true;
-            tm.frontier_empty_tx.send((true, tm.i)).unwrap();
             continue;
+        }
+        drop(f);
+
+        // Steal from another worker before declaring the frontier empty.
+        if let Some(next) = steal_from_others(&tm) {
+            rabin_scott_worker_mt_explore_loop(
+                &tm,
+                next,
+                &mut pool,
+                &mut local_transitions,
+                &mut local_accepts,
+            );
+        } else if !tm.idle[tm.i].load(Ordering::SeqCst) {
+            // Declared under the frontier lock, so that it is ordered with the pushes onto it.
+            let f = tm.frontiers[tm.i].lock().unwrap();
+            if f.is_empty() {
+                tm.idle[tm.i].store(true, Ordering::SeqCst);
+                tm.frontier_empty_tx.send((true, tm.i)).unwrap();
+            }
         } else if tm.stop_sig.load(Ordering::Relaxed) {
             local_transitions
                 .drain(..)
@@ -190,54 +213,79 @@ fn rabin_scott_worker_mt(tm: RabinScottWorkerThreadMembers) {
     }
 }
 
+/// Pop a set from the back of the frontier of another worker. A worker that declared itself
+/// idle declares itself busy again while holding the lock of the frontier it steals from, so
+/// that the stolen set stays accounted for when the owner declares its frontier empty.
+fn steal_from_others<S: StateSet>(tm: &RabinScottWorkerThreadMembers<S>) -> Option<S> {
+    (1..tm.n_threads)
+        .map(|offset| (tm.i + offset) % tm.n_threads)
+        .find_map(|j| {
+            let mut f = tm.frontiers[j].lock().unwrap();
+            let next = f.pop_back()?;
+            if tm.idle[tm.i].swap(false, Ordering::SeqCst) {
+                tm.frontier_empty_tx.send((false, tm.i)).unwrap();
+            }
+            Some(next)
+        })
+}
+
 /// Explore-state loop of a superset construction worker thread -
 /// Main component of superset construction.
-fn rabin_scott_worker_mt_explore_loop(
-    tm: &RabinScottWorkerThreadMembers,
-    next: Ubig,
+/// Successor sets are computed in batches of letters into the worker's buffer pool,
+/// so that memory per explored state is bounded by the batch size rather than the alphabet.
+fn rabin_scott_worker_mt_explore_loop<S: StateSet>(
+    tm: &RabinScottWorkerThreadMembers<S>,
+    next: S,
+    pool: &mut [S],
     local_transitions: &mut Vec<Transition>,
     local_accepts: &mut Vec<usize>,
 ) {
-    for a in 1..&tm.aut.alphabet + 1 {
-        let mut new_s = Ubig::new();
-        for s in next.get_seq() {
-            tm.transition_arr[a][s].iter().for_each(|t| {
-                tm.aut.add_state(&tm.transition_arr, &mut new_s, *t);
-            });
-        }
-        let compressed_new_s = new_s.clone().compress();
-        // Get hashes for given state and new state
-        let hash_next = get_hash(&next, tm.n_threads);
-        let hash_new = get_hash(&new_s, tm.n_threads);
+    let hash_next = get_hash(&next, tm.n_threads);
+    let id_next = *tm.num_maps[hash_next]
+        .lock()
+        .unwrap()
+        .get(&next.to_key())
+        .unwrap();
+    let next_seq = next.get_seq();
 
-        // Get shared num mapper HashMap and perform ops on shared memory.
-        let mut num_map_new = tm.num_maps[hash_new].lock().unwrap();
-        let is_new = !num_map_new.contains_key(&compressed_new_s);
-        if is_new {
-            num_map_new.insert(compressed_new_s.clone(), get_new_id());
+    for batch_start in (1..tm.aut.alphabet + 1).step_by(LETTER_BATCH_SIZE) {
+        let letters = batch_start..(batch_start + LETTER_BATCH_SIZE).min(tm.aut.alphabet + 1);
+        for (a, new_s) in letters.clone().zip(pool.iter_mut()) {
+            new_s.clear();
+            for s in &next_seq {
+                tm.transition_arr[a][*s].iter().for_each(|t| {
+                    tm.aut.add_state(&tm.transition_arr, new_s, *t);
+                });
+            }
         }
-        let id_new = *num_map_new.get(&compressed_new_s).unwrap();
-        drop(num_map_new);
 
-        let next_compressed = next.clone().compress();
-        let id_next = *tm.num_maps[hash_next]
-            .lock()
-            .unwrap()
-            .get(&next_compressed)
-            .unwrap();
-        local_transitions.push((id_next, a, id_new));
-        if is_new {
-            for s in new_s.get_seq().iter() {
-                if tm.end.contains(s) {
-                    local_accepts.push(id_new);
-                    break;
+        for (a, new_s) in letters.zip(pool.iter()) {
+            let hash_new = get_hash(new_s, tm.n_threads);
+
+            // Get shared num mapper HashMap and perform ops on shared memory.
+            let mut num_map_new = tm.num_maps[hash_new].lock().unwrap();
+            let (is_new, id_new) = match num_map_new.entry(new_s.to_key()) {
+                Entry::Occupied(e) => (false, *e.get()),
+                Entry::Vacant(e) => (true, *e.insert(tm.next_id.fetch_add(1, Ordering::Relaxed))),
+            };
+            drop(num_map_new);
+
+            local_transitions.push((id_next, a, id_new));
+            if is_new {
+                for s in new_s.get_seq().iter() {
+                    if tm.end.contains(s) {
+                        local_accepts.push(id_new);
+                        break;
+                    }
                 }
+                let mut new_frontier = tm.frontiers[hash_new].lock().unwrap();
+                // The owner may have been declared idle before a thief took the last set pushed
+                // onto its frontier, so the flag rather than the frontier says whether it is.
+                if tm.idle[hash_new].swap(false, Ordering::SeqCst) {
+                    tm.frontier_empty_tx.send((false, hash_new)).unwrap();
+                }
+                new_frontier.push_back(new_s.clone());
             }
-            let mut new_frontier = tm.frontiers[hash_new].lock().unwrap();
-            if new_frontier.len() == 0 {
-                tm.frontier_empty_tx.send((false, hash_new)).unwrap();
-            }
-            new_frontier.push_back(new_s);
         }
     }
 }
@@ -246,40 +294,9 @@ fn rabin_scott_worker_mt_explore_loop(
 // Helper Functions //
 //////////////////////
 
-fn add_transition(
-    transition: Transition,
-    transitions: &mut Vec<Transition>,
-    id_state_map: &mut HashMapXX<usize, usize>,
-) {
-    let (s, a, e) = transition;
-    let new_states = vec![s.clone(), e.clone()];
-    new_states.iter().for_each(|ns| {
-        if !id_state_map.contains_key(ns) {
-            id_state_map.insert(*ns, id_state_map.len());
-        }
-    });
-    transitions.push((
-        *id_state_map.get(&s).unwrap(),
-        a,
-        *id_state_map.get(&e).unwrap(),
-    ));
-}
-
-fn add_accept(s: usize, accepts: &mut Vec<usize>, id_state_map: &mut HashMapXX<usize, usize>) {
-    if !id_state_map.contains_key(&s) {
-        id_state_map.insert(s.clone(), id_state_map.len());
-    }
-    accepts.push(*id_state_map.get(&s).unwrap());
-}
-
-/// Get the hash of a Ubig
-fn get_hash(u: &Ubig, n: usize) -> usize {
+/// Get the hash of a set of states, to pick the thread owning it.
+fn get_hash<S: StateSet>(u: &S, n: usize) -> usize {
     let mut hasher = xx::Hasher64::default();
-    hasher.write(&u.num);
+    u.hash(&mut hasher);
     (hasher.finish() as usize) % n
 }
-
-/// Get a random new ID to assign to a state
-fn get_new_id() -> usize {
-    Uuid::new_v4().as_u128() as usize
-}
@@ -0,0 +1,152 @@
+use crate::automaton::{Automaton, AutomatonType};
+
+/// Packing of tuples of letters, one per track, into the single letters of an automaton.
+/// Track letters range over `0..=track_alphabets[i]`, where 0 means the track reads nothing,
+/// so that reading nothing on every track packs to the empty char 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiTrack {
+    pub track_alphabets: Vec<usize>,
+}
+
+impl MultiTrack {
+    /// Return a new multi-track packing with the given alphabet size per track.
+    pub fn new(track_alphabets: Vec<usize>) -> MultiTrack {
+        MultiTrack { track_alphabets }
+    }
+
+    /// Get the size of the packed alphabet.
+    pub fn alphabet(&self) -> usize {
+        self.track_alphabets
+            .iter()
+            .map(|k| k + 1)
+            .product::<usize>()
+            - 1
+    }
+
+    /// Pack one letter per track into a single letter.
+    pub fn pack(&self, tracks: &[usize]) -> usize {
+        assert_eq!(
+            tracks.len(),
+            self.track_alphabets.len(),
+            "Expected one letter per track!"
+        );
+        let mut letter = 0;
+        for (t, k) in tracks.iter().zip(&self.track_alphabets).rev() {
+            assert!(t <= k, "Letter {} is outside of its track alphabet!", t);
+            letter = letter * (k + 1) + t;
+        }
+        letter
+    }
+
+    /// Unpack a single letter into one letter per track.
+    pub fn unpack(&self, mut letter: usize) -> Vec<usize> {
+        assert!(
+            letter <= self.alphabet(),
+            "Letter {} is not packed!",
+            letter
+        );
+        self.track_alphabets
+            .iter()
+            .map(|k| {
+                let t = letter % (k + 1);
+                letter /= k + 1;
+                t
+            })
+            .collect()
+    }
+
+    /// Return an automaton over the packed alphabet from transitions labelled with one letter per
+    /// track.
+    pub fn automaton(
+        &self,
+        automaton_type: AutomatonType,
+        size: usize,
+        table: Vec<(usize, Vec<usize>, usize)>,
+        start: Vec<usize>,
+        end: Vec<usize>,
+    ) -> Automaton {
+        Automaton::new(
+            automaton_type,
+            size,
+            self.alphabet(),
+            table
+                .into_iter()
+                .map(|(s, tracks, e)| (s, self.pack(&tracks), e))
+                .collect(),
+            start,
+            end,
+        )
+    }
+
+    /// Get the transitions of an automaton over the packed alphabet with their letters unpacked.
+    pub fn unpacked_table(&self, aut: &Automaton) -> Vec<(usize, Vec<usize>, usize)> {
+        aut.table
+            .iter()
+            .map(|(s, a, e)| (*s, self.unpack(*a), *e))
+            .collect()
+    }
+}
+
+impl Automaton {
+    /// Return the composition of two 2-track automata, packed with `self_tracks` and
+    /// `other_tracks`, along with its packing - It reads the input track of `self` and writes
+    /// the output track of `other`, when the output track of `self` matches the input track of
+    /// `other`. A letter 0 on the matched track does not need a match: the automaton with it
+    /// moves alone, while the other one stays in its state.
+    /// State `(p, q)` of the composition has id `p * other.size + q`.
+    /// Panics if the automata do not have 2 tracks, or the matched tracks have different
+    /// alphabets.
+    pub fn compose(
+        &self,
+        other: &Automaton,
+        self_tracks: &MultiTrack,
+        other_tracks: &MultiTrack,
+    ) -> (Automaton, MultiTrack) {
+        assert!(
+            self_tracks.track_alphabets.len() == 2 && other_tracks.track_alphabets.len() == 2,
+            "Expected 2-track automata!"
+        );
+        assert_eq!(
+            self_tracks.track_alphabets[1], other_tracks.track_alphabets[0],
+            "Matched tracks have different alphabets!"
+        );
+        let tracks = MultiTrack::new(vec![
+            self_tracks.track_alphabets[0],
+            other_tracks.track_alphabets[1],
+        ]);
+        let id = |p: usize, q: usize| p * other.size + q;
+        let self_table = self_tracks.unpacked_table(self);
+        let other_table = other_tracks.unpacked_table(other);
+
+        let mut table: Vec<(usize, usize, usize)> = Vec::new();
+        for (p, x, p_next) in &self_table {
+            if x[1] == 0 {
+                (0..other.size)
+                    .for_each(|q| table.push((id(*p, q), tracks.pack(&[x[0], 0]), id(*p_next, q))));
+                continue;
+            }
+            for (q, y, q_next) in other_table.iter().filter(|(_, y, _)| y[0] == x[1]) {
+                table.push((id(*p, *q), tracks.pack(&[x[0], y[1]]), id(*p_next, *q_next)));
+            }
+        }
+        for (q, y, q_next) in other_table.iter().filter(|(_, y, _)| y[0] == 0) {
+            (0..self.size)
+                .for_each(|p| table.push((id(p, *q), tracks.pack(&[0, y[1]]), id(p, *q_next))));
+        }
+
+        let pairs = |a: &[usize], b: &[usize]| -> Vec<usize> {
+            a.iter()
+                .flat_map(|p| b.iter().map(move |q| id(*p, *q)))
+                .collect()
+        };
+        let ret = Automaton::new(
+            AutomatonType::NonDet,
+            self.size * other.size,
+            tracks.alphabet(),
+            table,
+            pairs(&self.start, &other.start),
+            pairs(&self.end, &other.end),
+        );
+        (ret, tracks)
+    }
+}
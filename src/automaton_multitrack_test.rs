@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use crate::automaton::{AlgorithmKind, AutomatonType};
+    use crate::automaton_multitrack::MultiTrack;
+
+    #[test]
+    // Test whether every packed letter of two binary tracks unpacks to itself.
+    fn test_pack_unpack() {
+        let tracks = MultiTrack::new(vec![2, 2]);
+        assert_eq!(tracks.alphabet(), 8);
+        assert_eq!(tracks.pack(&[0, 0]), 0);
+        for letter in 0..tracks.alphabet() + 1 {
+            assert_eq!(tracks.pack(&tracks.unpack(letter)), letter);
+        }
+    }
+
+    #[test]
+    // Test whether a two-track NFA determinizes over the packed alphabet.
+    fn test_multitrack_determinization() {
+        let tracks = MultiTrack::new(vec![2, 2]);
+        let two_track_nd = tracks.automaton(
+            AutomatonType::NonDet,
+            3,
+            vec![
+                (0, vec![1, 1], 1),
+                (0, vec![1, 1], 2),
+                (1, vec![2, 1], 1),
+                (2, vec![1, 2], 2),
+            ],
+            vec![0],
+            vec![1, 2],
+        );
+        let two_track_d = two_track_nd
+            .determinized(AlgorithmKind::Sequential)
+            .order_transitions();
+        // Start, {1, 2}, {1}, {2} and the sink.
+        assert_eq!(two_track_d.size, 5);
+        assert_eq!(two_track_d.alphabet, 8);
+        let unpacked = tracks.unpacked_table(&two_track_d);
+        assert_eq!(unpacked.len(), 5 * 8);
+        let (_, _, target) = unpacked
+            .iter()
+            .find(|(s, tracks, _)| *s == 0 && *tracks == vec![1, 1])
+            .unwrap();
+        assert!(two_track_d.end.contains(target));
+        assert!(two_track_d.equivalent(&two_track_nd));
+    }
+
+    #[test]
+    // Test whether composing the identity transducer with another one gives the other one.
+    fn test_compose_identity() {
+        let tracks = MultiTrack::new(vec![2, 2]);
+        let identity = tracks.automaton(
+            AutomatonType::NonDet,
+            1,
+            vec![(0, vec![1, 1], 0), (0, vec![2, 2], 0)],
+            vec![0],
+            vec![0],
+        );
+        let swap_then_erase = tracks.automaton(
+            AutomatonType::NonDet,
+            3,
+            vec![
+                (0, vec![1, 2], 1),
+                (0, vec![2, 1], 1),
+                (1, vec![0, 1], 2),
+                (2, vec![2, 0], 2),
+            ],
+            vec![0],
+            vec![2],
+        );
+        let (composed, composed_tracks) = identity.compose(&swap_then_erase, &tracks, &tracks);
+        assert_eq!(composed_tracks, tracks);
+        assert_eq!(composed.size, swap_then_erase.size);
+        assert_eq!(
+            composed.clone().order_transitions().table,
+            swap_then_erase.clone().order_transitions().table
+        );
+        assert!(composed.equivalent(&swap_then_erase));
+    }
+}
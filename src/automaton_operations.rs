@@ -0,0 +1,672 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use crate::automaton::{AlgorithmKind, Automaton, AutomatonType};
+
+impl Automaton {
+    /// Return the automaton of the residual language of the given prefix - Using a Brzozowski
+    /// derivative. The prefix is simulated from the start states and the states reached become
+    /// the new start states.
+    pub fn derivative(&self, prefix: &[usize]) -> Automaton {
+        let transition_arr = self.get_transition_array();
+        let mut set = self.get_start_set(&transition_arr);
+        for a in prefix {
+            set = self.get_next_set(&transition_arr, &set, *a);
+        }
+
+        let mut ret = self.clone();
+        ret.start = set.get_seq();
+        ret
+    }
+
+    /// Return the derivative of the automaton by each letter `1..=alphabet`, in order - The start
+    /// set is closed over empty char transitions once for all letters.
+    pub fn letter_derivatives(&self) -> Vec<Automaton> {
+        let transition_arr = self.get_transition_array();
+        let start_set = self.get_start_set(&transition_arr);
+        (1..self.alphabet + 1)
+            .map(|a| {
+                let mut ret = self.clone();
+                ret.start = self.get_next_set(&transition_arr, &start_set, a).get_seq();
+                ret
+            })
+            .collect()
+    }
+
+    /// Return the automaton with its start states replaced by the given ones.
+    /// Returns an error if a start state is out of range.
+    pub fn with_start(&self, start: &[usize]) -> Result<Automaton, String> {
+        if let Some(s) = start.iter().find(|s| **s >= self.size) {
+            return Err(format!(
+                "Start state {} is out of range for an automaton of {} states!",
+                s, self.size
+            ));
+        }
+        let mut ret = self.clone();
+        ret.start = start.to_vec();
+        Ok(ret)
+    }
+
+    /// Return the automaton recognizing the union of the languages of both automata - The states
+    /// of `other` are numbered after those of `self`, and a new start state is added last with
+    /// empty char transitions to the start states of both automata.
+    /// Panics if the alphabets differ.
+    pub fn union(&self, other: &Automaton) -> Automaton {
+        Automaton::union_all(&[self.clone(), other.clone()])
+    }
+
+    /// Return the automaton recognizing the concatenation of the languages of both automata -
+    /// The states of `other` are numbered after those of `self`, and empty char transitions lead
+    /// from the accept states of `self` to the start states of `other`. Only the accept states of
+    /// `other` accept, which covers the words of `self` when `other` accepts the empty word.
+    /// Panics if the alphabets differ.
+    pub fn concat(&self, other: &Automaton) -> Automaton {
+        Automaton::concat_all(&[self.clone(), other.clone()])
+    }
+
+    /// Return the automaton recognizing the Kleene star of the language of the automaton - A new
+    /// accepting start state is added last with empty char transitions to the old start states,
+    /// and empty char transitions lead from the old accept states back to the old start states.
+    pub fn kleene_star(&self) -> Automaton {
+        let (mut ret, _) = Automaton::disjoint_sum(std::slice::from_ref(self));
+        let start = ret.size;
+        ret.size += 1;
+        ret.table.extend(self.start.iter().map(|s| (start, 0, *s)));
+        for e in &self.end {
+            ret.table.extend(self.start.iter().map(|s| (*e, 0, *s)));
+        }
+        ret.start = vec![start];
+        ret.end = self.end.clone();
+        ret.end.push(start);
+        ret
+    }
+
+    /// Return the automaton recognizing the union of the languages of all automata, giving each
+    /// automaton a disjoint range of state ids in a single pass. The new start state comes last.
+    /// Panics if the alphabets differ.
+    pub fn union_all(auts: &[Automaton]) -> Automaton {
+        let (mut ret, offsets) = Automaton::disjoint_sum(auts);
+        let start = ret.size;
+        ret.size += 1;
+        for (aut, offset) in auts.iter().zip(offsets) {
+            ret.table
+                .extend(aut.start.iter().map(|s| (start, 0, s + offset)));
+            ret.end.extend(aut.end.iter().map(|e| e + offset));
+        }
+        ret.start = vec![start];
+        ret
+    }
+
+    /// Return the automaton recognizing the concatenation of the languages of all automata in
+    /// order, giving each automaton a disjoint range of state ids in a single pass. Without any
+    /// automaton, the result only accepts the empty word.
+    /// Panics if the alphabets differ.
+    pub fn concat_all(auts: &[Automaton]) -> Automaton {
+        let (mut ret, offsets) = Automaton::disjoint_sum(auts);
+        if auts.is_empty() {
+            ret.size = 1;
+            ret.start = vec![0];
+            ret.end = vec![0];
+            return ret;
+        }
+        for (i, pair) in auts.windows(2).enumerate() {
+            for e in &pair[0].end {
+                ret.table.extend(
+                    pair[1]
+                        .start
+                        .iter()
+                        .map(|s| (e + offsets[i], 0, s + offsets[i + 1])),
+                );
+            }
+        }
+        ret.start = auts[0].start.clone();
+        ret.end = auts[auts.len() - 1]
+            .end
+            .iter()
+            .map(|e| e + offsets[auts.len() - 1])
+            .collect();
+        ret
+    }
+
+    /// Get the non deterministic automaton holding the states and transitions of all automata,
+    /// without start or accept states, along with the offset of the state ids of each automaton.
+    /// Empty char transitions kept apart from the table are moved into it as letter 0, and the
+    /// alphabet labels are kept if all automata share them.
+    fn disjoint_sum(auts: &[Automaton]) -> (Automaton, Vec<usize>) {
+        let alphabet = auts.first().map_or(0, |aut| aut.alphabet);
+        assert!(
+            auts.iter().all(|aut| aut.alphabet == alphabet),
+            "Automata alphabets differ!"
+        );
+        let mut offsets = Vec::with_capacity(auts.len());
+        let mut table = Vec::with_capacity(auts.iter().map(|aut| aut.table.len()).sum());
+        let mut size = 0;
+        for aut in auts {
+            offsets.push(size);
+            table.extend(aut.table.iter().map(|(s, a, e)| (s + size, *a, e + size)));
+            table.extend(
+                aut.epsilon_table
+                    .iter()
+                    .flatten()
+                    .map(|(s, e)| (s + size, 0, e + size)),
+            );
+            size += aut.size;
+        }
+        let mut ret = Automaton::new(AutomatonType::NonDet, size, alphabet, table, vec![], vec![]);
+        ret.alphabet_labels = Automaton::shared_alphabet_labels(auts.iter());
+        (ret, offsets)
+    }
+
+    /// Get the alphabet labels of the automata if they all have the same ones, or None.
+    fn shared_alphabet_labels<'a>(
+        mut auts: impl Iterator<Item = &'a Automaton>,
+    ) -> Option<Vec<String>> {
+        let labels = auts.next()?.alphabet_labels.as_ref()?;
+        match auts.all(|aut| aut.alphabet_labels.as_ref() == Some(labels)) {
+            true => Some(labels.clone()),
+            false => None,
+        }
+    }
+
+    /// Return the deterministic automaton recognizing the words in both languages - Using the
+    /// product of both automata, determinized first if non deterministic. State `(p, q)` of the
+    /// product has id `p * q_size + q`, and has a transition on a letter when both `p` and `q`
+    /// do. The alphabet labels are kept if both automata share them.
+    /// Panics if the alphabets differ.
+    pub fn intersection(&self, other: &Automaton) -> Automaton {
+        assert_eq!(self.alphabet, other.alphabet, "Automata alphabets differ!");
+        let dfa = |aut: &Automaton| match aut.automaton_type {
+            AutomatonType::Det => aut.clone(),
+            AutomatonType::NonDet => aut.determinized(AlgorithmKind::Sequential),
+        };
+        let (a, b) = (dfa(self), dfa(other));
+        // Target of each state on each letter, if any.
+        let targets = |aut: &Automaton| -> Vec<Vec<Option<usize>>> {
+            let arr = aut.get_transition_array();
+            (0..aut.size)
+                .map(|s| {
+                    (1..aut.alphabet + 1)
+                        .map(|c| arr[c][s].first().copied())
+                        .collect()
+                })
+                .collect()
+        };
+        let (targets_a, targets_b) = (targets(&a), targets(&b));
+        let (end_a, end_b): (HashSet<usize>, HashSet<usize>) = (
+            a.end.iter().copied().collect(),
+            b.end.iter().copied().collect(),
+        );
+        let id = |p: usize, q: usize| p * b.size + q;
+
+        let mut table = Vec::new();
+        let mut end = Vec::new();
+        for (p, row_a) in targets_a.iter().enumerate() {
+            for (q, row_b) in targets_b.iter().enumerate() {
+                for (c, next) in row_a.iter().zip(row_b).enumerate() {
+                    if let (Some(p_next), Some(q_next)) = next {
+                        table.push((id(p, q), c + 1, id(*p_next, *q_next)));
+                    }
+                }
+                if end_a.contains(&p) && end_b.contains(&q) {
+                    end.push(id(p, q));
+                }
+            }
+        }
+        let start = a
+            .start
+            .iter()
+            .flat_map(|p| b.start.iter().map(move |q| id(*p, *q)))
+            .collect();
+        let mut ret = Automaton::new(
+            AutomatonType::Det,
+            a.size * b.size,
+            self.alphabet,
+            table,
+            start,
+            end,
+        );
+        ret.alphabet_labels = Automaton::shared_alphabet_labels([self, other].into_iter());
+        ret
+    }
+
+    /// Return the deterministic automaton recognizing the words in exactly one of the languages
+    /// of both automata - Using the product of their complete determinizations, accepting when
+    /// exactly one side accepts. State `(p, q)` of the product has id `p * q_size + q`.
+    /// The alphabet labels are kept if both automata share them.
+    /// Panics if the alphabets differ.
+    pub fn symmetric_difference(&self, other: &Automaton) -> Automaton {
+        assert_eq!(self.alphabet, other.alphabet, "Automata alphabets differ!");
+        let complete_dfa = |aut: &Automaton| {
+            let dfa = aut.determinized(AlgorithmKind::Sequential).completed();
+            let matrix = dfa.transition_matrix().unwrap();
+            let end: HashSet<usize> = dfa.end.iter().copied().collect();
+            (dfa, matrix, end)
+        };
+        let (a, matrix_a, end_a) = complete_dfa(self);
+        let (b, matrix_b, end_b) = complete_dfa(other);
+        let id = |p: usize, q: usize| p * b.size + q;
+
+        let mut table = Vec::with_capacity(a.size * b.size * self.alphabet);
+        let mut end = Vec::new();
+        for (p, row_a) in matrix_a.iter().enumerate() {
+            for (q, row_b) in matrix_b.iter().enumerate() {
+                table.extend((0..self.alphabet).map(|c| (id(p, q), c + 1, id(row_a[c], row_b[c]))));
+                if end_a.contains(&p) != end_b.contains(&q) {
+                    end.push(id(p, q));
+                }
+            }
+        }
+        let start = a
+            .start
+            .iter()
+            .flat_map(|p| b.start.iter().map(move |q| id(*p, *q)))
+            .collect();
+        let mut ret = Automaton::new(
+            AutomatonType::Det,
+            a.size * b.size,
+            self.alphabet,
+            table,
+            start,
+            end,
+        );
+        ret.alphabet_labels = Automaton::shared_alphabet_labels([self, other].into_iter());
+        ret
+    }
+
+    /// Return the automaton recognizing the words of the language of length at most `max_len` -
+    /// Using the product with a `max_len + 1` state DFA counting letters read.
+    /// State `(s, k)` of the product, where `k` letters were read, has id `s * (max_len + 1) + k`.
+    /// Empty char transitions kept apart from the table are moved into it as letter 0.
+    pub fn truncate_length(&self, max_len: usize) -> Automaton {
+        let counters = max_len + 1;
+        let id = |s: usize, k: usize| s * counters + k;
+
+        let mut ret = self.clone();
+        ret.size = self.size * counters;
+        ret.table = self
+            .transitions_with_epsilon()
+            .flat_map(|(s, a, e)| {
+                (0..counters)
+                    .filter(move |k| a == 0 || *k < max_len)
+                    .map(move |k| (id(s, k), a, id(e, if a == 0 { k } else { k + 1 })))
+            })
+            .collect();
+        ret.epsilon_table = None;
+        ret.probabilities = None;
+        ret.start = self.start.iter().map(|s| id(*s, 0)).collect();
+        ret.end = self
+            .end
+            .iter()
+            .flat_map(|e| (0..counters).map(move |k| id(*e, k)))
+            .collect();
+        ret.accept_priority = self.accept_priority.as_ref().map(|p| {
+            p.iter()
+                .flat_map(|p| (0..counters).map(move |_| *p))
+                .collect()
+        });
+        ret
+    }
+
+    /// Scale the transition probabilities so that the probabilities of the transitions leaving
+    /// each state sum to 1. States without any transition are left alone.
+    /// Returns an error if the probabilities do not match the table, or if a state has
+    /// transitions but no probability mass to scale.
+    pub fn normalize_probabilities(&mut self) -> Result<(), String> {
+        let probabilities = match &mut self.probabilities {
+            Some(p) => p,
+            None => return Ok(()),
+        };
+        if probabilities.len() != self.table.len() {
+            return Err(format!(
+                "{} probabilities for {} transitions!",
+                probabilities.len(),
+                self.table.len()
+            ));
+        }
+
+        let mut mass: HashMap<usize, f64> = HashMap::new();
+        self.table
+            .iter()
+            .zip(probabilities.iter())
+            .for_each(|((s, _, _), p)| *mass.entry(*s).or_insert(0.0) += p);
+        let mut massless: Vec<usize> = mass
+            .iter()
+            .filter(|(_, m)| **m <= 0.0)
+            .map(|(s, _)| *s)
+            .collect();
+        if !massless.is_empty() {
+            massless.sort();
+            return Err(format!(
+                "States {:?} have no outgoing probability mass!",
+                massless
+            ));
+        }
+        self.table
+            .iter()
+            .zip(probabilities.iter_mut())
+            .for_each(|((s, _, _), p)| *p /= mass[s]);
+        Ok(())
+    }
+
+    /// Return the deterministic automaton recognizing the words over the alphabet that the
+    /// automaton rejects - A non deterministic automaton is determinized first, then missing
+    /// transitions are sent to a sink before swapping accept and non accept states.
+    pub fn complement(&self) -> Automaton {
+        let mut ret = match self.automaton_type {
+            AutomatonType::Det => self.completed(),
+            AutomatonType::NonDet => self.determinized(AlgorithmKind::Sequential).completed(),
+        };
+        let end: HashSet<usize> = ret.end.iter().copied().collect();
+        ret.end = (0..ret.size).filter(|s| !end.contains(s)).collect();
+        ret.accept_priority = None;
+        ret
+    }
+
+    /// Return the automaton with its states renumbered in breadth-first order from the start
+    /// states, reading letters in increasing order, along with the permutation mapping each old
+    /// state id to its new id. Unreachable states come last, in their original order.
+    /// Empty char transitions kept apart from the table are moved into it as letter 0.
+    pub fn to_bfs_order(&self) -> (Automaton, Vec<usize>) {
+        let arr = self.get_transition_array();
+        let mut order: Vec<usize> = Vec::with_capacity(self.size);
+        let mut visited = vec![false; self.size];
+        let mut start = self.start.clone();
+        start.sort();
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        for s in start {
+            if !visited[s] {
+                visited[s] = true;
+                queue.push_back(s);
+            }
+        }
+        while let Some(s) = queue.pop_front() {
+            order.push(s);
+            for letter_arr in &arr {
+                let mut targets = letter_arr[s].clone();
+                targets.sort();
+                for e in targets {
+                    if !visited[e] {
+                        visited[e] = true;
+                        queue.push_back(e);
+                    }
+                }
+            }
+        }
+        order.extend((0..self.size).filter(|s| !visited[*s]));
+
+        let mut permutation = vec![0; self.size];
+        order
+            .iter()
+            .enumerate()
+            .for_each(|(new, old)| permutation[*old] = new);
+        let map_sorted = |v: &Vec<usize>| -> Vec<usize> {
+            let mut mapped: Vec<usize> = v.iter().map(|s| permutation[*s]).collect();
+            mapped.sort();
+            mapped
+        };
+
+        let mut ret = self.clone();
+        ret.table = self
+            .transitions_with_epsilon()
+            .map(|(s, a, e)| (permutation[s], a, permutation[e]))
+            .collect();
+        ret.table.sort();
+        ret.epsilon_table = None;
+        ret.start = map_sorted(&self.start);
+        ret.end = map_sorted(&self.end);
+        ret.accept_priority = None;
+        ret.probabilities = None;
+        (ret, permutation)
+    }
+
+    /// Return the automaton with its states renumbered in breadth-first order - See
+    /// `to_bfs_order`. Isomorphic DFAs have the same canonical form.
+    pub fn canonicalize(&self) -> Automaton {
+        self.to_bfs_order().0
+    }
+
+    /// Return the automaton over an alphabet of letter classes, where letters with the same
+    /// transitions from every state are merged into one class, along with the letters of each
+    /// class. Class `i + 1` stands for the letters `classes[i]`, and classes are ordered by their
+    /// smallest letter. Empty char transitions are kept as they are. A class is labelled with the
+    /// labels of its letters joined by `|`.
+    pub fn compress_symbol_classes(&self) -> (Automaton, Vec<Vec<usize>>) {
+        let mut behaviours: Vec<BTreeSet<(usize, usize)>> =
+            vec![BTreeSet::new(); self.alphabet + 1];
+        self.table.iter().for_each(|(s, a, e)| {
+            behaviours[*a].insert((*s, *e));
+        });
+
+        let mut classes: Vec<Vec<usize>> = Vec::new();
+        let mut class_of_behaviour: HashMap<&BTreeSet<(usize, usize)>, usize> = HashMap::new();
+        let mut letter_class = vec![0; self.alphabet + 1];
+        for a in 1..self.alphabet + 1 {
+            let class = *class_of_behaviour.entry(&behaviours[a]).or_insert_with(|| {
+                classes.push(Vec::new());
+                classes.len()
+            });
+            classes[class - 1].push(a);
+            letter_class[a] = class;
+        }
+
+        let mut ret = self.clone();
+        ret.alphabet = classes.len();
+        ret.alphabet_labels = self.alphabet_labels.as_ref().map(|_| {
+            classes
+                .iter()
+                .map(|class| {
+                    let labels: Vec<String> = class.iter().map(|a| self.letter_label(*a)).collect();
+                    labels.join("|")
+                })
+                .collect()
+        });
+        ret.table = self
+            .table
+            .iter()
+            .filter(|(_, a, _)| *a == 0 || classes[letter_class[*a] - 1][0] == *a)
+            .map(|(s, a, e)| (*s, letter_class[*a], *e))
+            .collect();
+        ret.probabilities = None;
+        (ret, classes)
+    }
+
+    /// Return the automaton over the original alphabet of an automaton over letter classes,
+    /// each transition on a class becoming a transition on each of its letters. The letters of
+    /// the original alphabet have no labels, as classes do not tell their names apart.
+    pub fn expand_symbol_classes(&self, classes: &[Vec<usize>]) -> Automaton {
+        let mut ret = self.clone();
+        ret.alphabet = classes.iter().flatten().copied().max().unwrap_or(0);
+        ret.alphabet_labels = None;
+        ret.table = self
+            .table
+            .iter()
+            .flat_map(|(s, a, e)| match a {
+                0 => vec![(*s, 0, *e)],
+                _ => classes[a - 1].iter().map(|l| (*s, *l, *e)).collect(),
+            })
+            .collect();
+        ret.probabilities = None;
+        ret
+    }
+
+    /// Remove repeated transitions, empty char ones included, keeping the first occurrence of
+    /// each. Probabilities stay aligned with the remaining transitions.
+    pub fn dedup_transitions(&mut self) {
+        let mut seen: HashSet<(usize, usize, usize)> = HashSet::new();
+        let keep: Vec<bool> = self.table.iter().map(|t| seen.insert(*t)).collect();
+        let mut kept = keep.iter();
+        self.table.retain(|_| *kept.next().unwrap());
+        if let Some(probabilities) = &mut self.probabilities {
+            let mut kept = keep.iter();
+            probabilities.retain(|_| *kept.next().unwrap());
+        }
+    }
+
+    /// Return a smaller automaton whose determinization still has more than `ratio` times as
+    /// many states as it has - Greedily removing transitions, then states left without
+    /// transitions, for as long as the blow-up stays above the ratio. Returns the automaton
+    /// unchanged if its own blow-up is not above the ratio.
+    pub fn shrink_to_blowup(&self, ratio: f64) -> Automaton {
+        let blows_up = |aut: &Automaton| {
+            aut.size > 0
+                && aut.determinized(AlgorithmKind::Sequential).size as f64 / aut.size as f64 > ratio
+        };
+        let mut ret = self.clone();
+        ret.probabilities = None;
+        if !blows_up(&ret) {
+            return ret;
+        }
+
+        let mut i = 0;
+        while i < ret.table.len() {
+            let mut smaller = ret.clone();
+            smaller.table.remove(i);
+            if blows_up(&smaller) {
+                ret = smaller;
+            } else {
+                i += 1;
+            }
+        }
+        let mut used: HashSet<usize> = ret
+            .transitions_with_epsilon()
+            .flat_map(|(s, _, e)| [s, e])
+            .collect();
+        used.extend(ret.start.iter().copied());
+        let unused: HashSet<usize> = (0..ret.size).filter(|s| !used.contains(s)).collect();
+        let smaller = ret.without_states(&unused);
+        if blows_up(&smaller) {
+            ret = smaller;
+        }
+        ret
+    }
+
+    /// Return the automaton with every missing transition leading to a new sink state.
+    pub fn completed(&self) -> Automaton {
+        self.complete_to_sink_id().0
+    }
+
+    /// Return the completed automaton along with the id of the sink state, or None if the
+    /// automaton was already complete and no sink was added.
+    pub fn complete_to_sink_id(&self) -> (Automaton, Option<usize>) {
+        let mut defined = vec![vec![false; self.alphabet + 1]; self.size];
+        self.table
+            .iter()
+            .filter(|(s, a, _)| *s < self.size && *a <= self.alphabet)
+            .for_each(|(s, a, _)| defined[*s][*a] = true);
+        let missing: Vec<(usize, usize)> = (0..self.size)
+            .flat_map(|s| (1..self.alphabet + 1).map(move |a| (s, a)))
+            .filter(|(s, a)| !defined[*s][*a])
+            .collect();
+        if missing.is_empty() {
+            return (self.clone(), None);
+        }
+
+        let sink = self.size;
+        let mut ret = self.clone();
+        ret.size += 1;
+        ret.table
+            .extend(missing.into_iter().map(|(s, a)| (s, a, sink)));
+        ret.table
+            .extend((1..self.alphabet + 1).map(|a| (sink, a, sink)));
+        ret.probabilities = None;
+        (ret, Some(sink))
+    }
+
+    /// Return the automaton with every missing transition leading to a new error state, along
+    /// with the id of the error state. Unlike `complete_to_sink_id`, the error state is added
+    /// even if the automaton is already complete, so that the id is always a new state - It is
+    /// unreachable then, and `run` never reports an error. Probabilities are dropped.
+    pub fn with_error_state(&self) -> (Automaton, usize) {
+        let (mut ret, sink) = self.complete_to_sink_id();
+        if let Some(sink) = sink {
+            return (ret, sink);
+        }
+        let error = ret.size;
+        ret.size += 1;
+        ret.table
+            .extend((1..self.alphabet + 1).map(|a| (error, a, error)));
+        ret.probabilities = None;
+        (ret, error)
+    }
+
+    /// Return the automaton without its dead sink, leaving a partial automaton.
+    /// The sink is only removed if it is the single state from which no accept state can be
+    /// reached, and it is not a start state.
+    pub fn without_sink(&self) -> Automaton {
+        let live = self.get_live_states();
+        let dead: Vec<usize> = (0..self.size).filter(|s| !live.contains(s)).collect();
+        match dead[..] {
+            [sink] if !self.start.contains(&sink) => self.without_states(&HashSet::from([sink])),
+            _ => self.clone(),
+        }
+    }
+
+    /// Return the sub-automaton induced by the states reachable from the given states, with them
+    /// as its start states. The remaining states are renumbered in order.
+    /// Panics if a given state is out of range.
+    pub fn reachable_subgraph(&self, from: &[usize]) -> Automaton {
+        let arr = self.get_transition_array();
+        let mut reachable: HashSet<usize> = from.iter().copied().collect();
+        let mut queue: VecDeque<usize> = from.iter().copied().collect();
+        while let Some(s) = queue.pop_front() {
+            for letter_arr in &arr {
+                for e in &letter_arr[s] {
+                    if reachable.insert(*e) {
+                        queue.push_back(*e);
+                    }
+                }
+            }
+        }
+
+        let mut ret = self.clone();
+        ret.start = from.to_vec();
+        let unreachable: HashSet<usize> =
+            (0..self.size).filter(|s| !reachable.contains(s)).collect();
+        ret.without_states(&unreachable)
+    }
+
+    /// Return the automaton with the given states and all their transitions removed.
+    /// The remaining states are renumbered in order, and accept priorities stay aligned with the
+    /// remaining accept states.
+    pub(crate) fn without_states(&self, removed: &HashSet<usize>) -> Automaton {
+        let state_map: HashMap<usize, usize> = (0..self.size)
+            .filter(|s| !removed.contains(s))
+            .enumerate()
+            .map(|(new, old)| (old, new))
+            .collect();
+        let map_vec = |v: &Vec<usize>| -> Vec<usize> {
+            v.iter().filter_map(|s| state_map.get(s).copied()).collect()
+        };
+
+        let mut ret = self.clone();
+        ret.size = state_map.len();
+        ret.table = self
+            .table
+            .iter()
+            .filter_map(|(s, a, e)| match (state_map.get(s), state_map.get(e)) {
+                (Some(s), Some(e)) => Some((*s, *a, *e)),
+                _ => None,
+            })
+            .collect();
+        ret.epsilon_table = self.epsilon_table.as_ref().map(|epsilon_table| {
+            epsilon_table
+                .iter()
+                .filter_map(|(s, e)| match (state_map.get(s), state_map.get(e)) {
+                    (Some(s), Some(e)) => Some((*s, *e)),
+                    _ => None,
+                })
+                .collect()
+        });
+        ret.start = map_vec(&self.start);
+        ret.end = map_vec(&self.end);
+        ret.accept_priority = self.accept_priority.as_ref().map(|priority| {
+            self.end
+                .iter()
+                .zip(priority)
+                .filter(|(e, _)| state_map.contains_key(e))
+                .map(|(_, p)| *p)
+                .collect()
+        });
+        ret.probabilities = None;
+        ret
+    }
+}
@@ -0,0 +1,704 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::automaton::{AlgorithmKind, Automaton, AutomatonType, RunOutcome};
+
+    #[test]
+    // Test whether the derivative of ab·c* by a recognizes b·c*.
+    fn test_derivative_prefix() {
+        let abc_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            3,
+            vec![(0, 1, 1), (1, 2, 2), (2, 3, 2)],
+            vec![0],
+            vec![2],
+        );
+        let bc_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            3,
+            vec![(0, 2, 1), (1, 3, 1)],
+            vec![0],
+            vec![1],
+        );
+        let derivative = abc_nd.derivative(&[1]);
+        assert_eq!(derivative.start, vec![1]);
+        assert_eq!(
+            derivative
+                .determinized(AlgorithmKind::Sequential)
+                .minimized()
+                .table,
+            bc_nd
+                .determinized(AlgorithmKind::Sequential)
+                .minimized()
+                .table
+        );
+    }
+
+    #[test]
+    // Test whether a derivative follows empty char transitions and drops dead prefixes.
+    fn test_derivative_empty_char() {
+        let empty_char_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 0, 2), (2, 2, 2)],
+            vec![0],
+            vec![2],
+        );
+        assert_eq!(empty_char_nd.derivative(&[1]).start, vec![1, 2]);
+        assert_eq!(empty_char_nd.derivative(&[2]).start, Vec::<usize>::new());
+        assert_eq!(empty_char_nd.derivative(&[]).start, vec![0]);
+    }
+
+    #[test]
+    // Test whether the sink of a minimal DFA is dropped without changing its language.
+    fn test_minimized_partial_sinkhole() {
+        let sinkhole_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 1, 2)],
+            vec![0],
+            vec![2],
+        );
+        let sinkhole_d = sinkhole_nd.determinized(AlgorithmKind::Sequential);
+        let partial = sinkhole_d.minimized_partial();
+        assert_eq!(partial.size, 3);
+        assert_eq!(partial.table.len(), 2);
+        assert_eq!(partial.get_live_states().len(), partial.size);
+        assert!(partial.equivalent(&sinkhole_nd));
+    }
+
+    #[test]
+    // Test whether automata with several dead states keep all of them.
+    fn test_without_sink_several_dead() {
+        let two_dead_d = Automaton::new(
+            AutomatonType::Det,
+            3,
+            1,
+            vec![(0, 1, 1), (1, 1, 2), (2, 1, 2)],
+            vec![0],
+            vec![0],
+        );
+        assert_eq!(two_dead_d.without_sink().size, 3);
+    }
+
+    #[test]
+    // Test whether completing a partial DFA adds a sink looping on every letter.
+    fn test_complete_to_sink_id() {
+        let partial_d = Automaton::new(
+            AutomatonType::Det,
+            2,
+            2,
+            vec![(0, 1, 1), (1, 2, 1)],
+            vec![0],
+            vec![1],
+        );
+        let (complete_d, sink) = partial_d.complete_to_sink_id();
+        let sink = sink.unwrap();
+        assert_eq!(sink, 2);
+        assert_eq!(complete_d.size, 3);
+        for a in 1..complete_d.alphabet + 1 {
+            assert!(complete_d.table.contains(&(sink, a, sink)));
+        }
+        assert!(complete_d.table.contains(&(0, 2, sink)));
+        assert!(complete_d.table.contains(&(1, 1, sink)));
+        assert!(complete_d.transition_matrix().is_some());
+        assert!(complete_d.equivalent(&partial_d));
+
+        let (same_d, no_sink) = complete_d.complete_to_sink_id();
+        assert_eq!(no_sink, None);
+        assert_eq!(same_d.table, complete_d.table);
+    }
+
+    #[test]
+    // Test whether truncating a* at length 3 accepts exactly the words up to aaa.
+    fn test_truncate_length() {
+        let a_star_d = Automaton::new(AutomatonType::Det, 1, 1, vec![(0, 1, 0)], vec![0], vec![0]);
+        let truncated = a_star_d.truncate_length(3);
+        assert_eq!(truncated.size, 4);
+        for len in 0..4 {
+            assert!(truncated.accepts(&vec![1; len]));
+        }
+        assert!(!truncated.accepts(&[1, 1, 1, 1]));
+        assert!(!truncated.accepts(&[1; 8]));
+        assert_eq!(
+            truncated
+                .determinized(AlgorithmKind::Sequential)
+                .minimized_partial()
+                .size,
+            4
+        );
+    }
+
+    #[test]
+    // Test whether the breadth-first permutation carries external state labels consistently.
+    fn test_to_bfs_order() {
+        let sinkhole_d = Automaton::new(
+            AutomatonType::Det,
+            4,
+            2,
+            vec![
+                (3, 1, 1),
+                (3, 2, 2),
+                (1, 1, 0),
+                (1, 2, 2),
+                (2, 1, 2),
+                (2, 2, 2),
+                (0, 1, 2),
+                (0, 2, 2),
+            ],
+            vec![3],
+            vec![0],
+        );
+        let labels = ["aa", "a", "sink", "start"];
+        let (bfs, permutation) = sinkhole_d.to_bfs_order();
+        assert_eq!(permutation, vec![3, 1, 2, 0]);
+
+        let mut bfs_labels = vec![""; labels.len()];
+        permutation
+            .iter()
+            .enumerate()
+            .for_each(|(old, new)| bfs_labels[*new] = labels[old]);
+        assert_eq!(bfs_labels, vec!["start", "a", "sink", "aa"]);
+        assert_eq!(bfs.start, vec![0]);
+        assert_eq!(bfs_labels[bfs.end[0]], "aa");
+        for (s, a, e) in &sinkhole_d.table {
+            assert!(bfs.table.contains(&(permutation[*s], *a, permutation[*e])));
+        }
+        assert_eq!(bfs.table, sinkhole_d.canonicalize().table);
+    }
+
+    #[test]
+    // Test whether normalizing a probabilistic automaton makes each state's outgoing mass 1.
+    fn test_normalize_probabilities() {
+        let mut pfa = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (0, 1, 2), (0, 2, 0), (1, 2, 2), (2, 1, 2)],
+            vec![0],
+            vec![2],
+        );
+        pfa.probabilities = Some(vec![1.0, 1.0, 2.0, 0.5, 3.0]);
+        pfa.normalize_probabilities().unwrap();
+
+        let probabilities = pfa.probabilities.as_ref().unwrap();
+        assert_eq!(probabilities, &vec![0.25, 0.25, 0.5, 1.0, 1.0]);
+        for s in 0..pfa.size {
+            let sum: f64 = pfa
+                .table
+                .iter()
+                .zip(probabilities.iter())
+                .filter(|((from, _, _), _)| *from == s)
+                .map(|(_, p)| p)
+                .sum();
+            assert!((sum - 1.0).abs() < 1e-12);
+        }
+
+        pfa.probabilities = Some(vec![1.0, 1.0, 2.0, 0.0, 3.0]);
+        assert!(pfa.normalize_probabilities().is_err());
+    }
+
+    fn get_small_automata() -> Vec<Automaton> {
+        vec![
+            // a
+            Automaton::new(AutomatonType::Det, 2, 2, vec![(0, 1, 1)], vec![0], vec![1]),
+            // b*
+            Automaton::new(AutomatonType::Det, 1, 2, vec![(0, 2, 0)], vec![0], vec![0]),
+            // ab
+            Automaton::new(
+                AutomatonType::NonDet,
+                3,
+                2,
+                vec![(0, 1, 1), (1, 2, 2)],
+                vec![0],
+                vec![2],
+            ),
+        ]
+    }
+
+    #[test]
+    // Test whether the batched union matches the left folded binary union.
+    fn test_union_all() {
+        let auts = get_small_automata();
+        let folded = auts[0].union(&auts[1]).union(&auts[2]);
+        let batched = Automaton::union_all(&auts);
+        assert_eq!(batched.size, 7);
+        assert!(batched.equivalent(&folded));
+        assert!(batched.accepts(&[]));
+        assert!(batched.accepts(&[1]));
+        assert!(batched.accepts(&[2, 2]));
+        assert!(batched.accepts(&[1, 2]));
+        assert!(!batched.accepts(&[2, 1]));
+    }
+
+    #[test]
+    // Test whether the union of `a+` and of `bb`, written with an empty char transition kept
+    // apart, needs both branches and determinizes and minimizes to their union.
+    fn test_union() {
+        let a_plus_d = Automaton::new(
+            AutomatonType::Det,
+            2,
+            2,
+            vec![(0, 1, 1), (1, 1, 1)],
+            vec![0],
+            vec![1],
+        );
+        let mut bb_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            2,
+            vec![(0, 2, 1), (2, 2, 3)],
+            vec![0],
+            vec![3],
+        );
+        bb_nd.epsilon_table = Some(vec![(1, 2)]);
+
+        let union = a_plus_d.union(&bb_nd);
+        assert_eq!(union.automaton_type, AutomatonType::NonDet);
+        assert_eq!(union.size, 7);
+        assert_eq!(union.start, vec![6]);
+        let min = union.determinized(AlgorithmKind::Sequential).minimized();
+        for word in [vec![1], vec![1, 1, 1], vec![2, 2]] {
+            assert!(min.accepts(&word));
+        }
+        for word in [vec![], vec![2], vec![1, 2], vec![2, 2, 2]] {
+            assert!(!min.accepts(&word));
+        }
+        assert!(min.equivalent(&union));
+    }
+
+    #[test]
+    #[should_panic(expected = "Automata alphabets differ!")]
+    // Test whether the union of automata over alphabets of different sizes is refused.
+    fn test_union_alphabets_differ() {
+        let a_d = Automaton::new(AutomatonType::Det, 1, 1, vec![], vec![0], vec![0]);
+        let ab_d = Automaton::new(AutomatonType::Det, 1, 2, vec![], vec![0], vec![0]);
+        a_d.union(&ab_d);
+    }
+
+    #[test]
+    // Test whether `a*` followed by `b` accepts `aab` but not words in the wrong order, and
+    // whether the empty word of a second language lets the first one end the word.
+    fn test_concat() {
+        let a_star_d = Automaton::new(AutomatonType::Det, 1, 2, vec![(0, 1, 0)], vec![0], vec![0]);
+        let b_d = Automaton::new(AutomatonType::Det, 2, 2, vec![(0, 2, 1)], vec![0], vec![1]);
+        let a_star_b = a_star_d.concat(&b_d);
+        assert_eq!(a_star_b.automaton_type, AutomatonType::NonDet);
+        assert!(a_star_b.accepts(&[1, 1, 2]));
+        assert!(a_star_b.accepts(&[2]));
+        assert!(!a_star_b.accepts(&[1, 2, 1]));
+        assert!(!a_star_b.accepts(&[2, 1]));
+        assert!(!a_star_b.accepts(&[1, 1]));
+
+        let b_a_star = b_d.concat(&a_star_d);
+        assert!(b_a_star.accepts(&[2]));
+        assert!(b_a_star.accepts(&[2, 1, 1]));
+        assert!(!b_a_star.accepts(&[1, 2]));
+    }
+
+    #[test]
+    // Test whether the star of `ab` accepts repetitions of `ab`, the empty word included, before
+    // and after determinization and minimization.
+    fn test_kleene_star() {
+        let ab_d = Automaton::new(
+            AutomatonType::Det,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 2, 2)],
+            vec![0],
+            vec![2],
+        );
+        let star = ab_d.kleene_star();
+        assert_eq!(star.automaton_type, AutomatonType::NonDet);
+        assert_eq!(star.size, 4);
+        let min = star.determinized(AlgorithmKind::Sequential).minimized();
+        for aut in [&star, &min] {
+            assert!(aut.accepts(&[]));
+            assert!(aut.accepts(&[1, 2]));
+            assert!(aut.accepts(&[1, 2, 1, 2]));
+            assert!(!aut.accepts(&[1, 2, 1]));
+            assert!(!aut.accepts(&[2, 1]));
+        }
+    }
+
+    #[test]
+    // Test whether the batched concatenation matches the left folded binary concatenation.
+    fn test_concat_all() {
+        let auts = get_small_automata();
+        let folded = auts[0].concat(&auts[1]).concat(&auts[2]);
+        let batched = Automaton::concat_all(&auts);
+        assert_eq!(batched.size, 6);
+        assert!(batched.equivalent(&folded));
+        assert!(batched.accepts(&[1, 1, 2]));
+        assert!(batched.accepts(&[1, 2, 2, 1, 2]));
+        assert!(!batched.accepts(&[1, 2]));
+        assert!(Automaton::concat_all(&[]).accepts(&[]));
+    }
+
+    #[test]
+    // Test whether two letters with the same transitions collapse into one class, and the
+    // determinization over classes expands back to an equivalent DFA.
+    fn test_compress_symbol_classes() {
+        let redundant_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            3,
+            vec![
+                (0, 1, 1),
+                (0, 1, 2),
+                (0, 2, 1),
+                (0, 2, 2),
+                (1, 3, 2),
+                (2, 0, 0),
+            ],
+            vec![0],
+            vec![2],
+        );
+        let (compressed, classes) = redundant_nd.compress_symbol_classes();
+        assert_eq!(classes, vec![vec![1, 2], vec![3]]);
+        assert_eq!(compressed.alphabet, 2);
+        assert_eq!(compressed.table.len(), 4);
+
+        let expanded = compressed
+            .determinized(AlgorithmKind::Sequential)
+            .expand_symbol_classes(&classes);
+        assert_eq!(expanded.alphabet, 3);
+        assert!(expanded.equivalent(&redundant_nd));
+    }
+
+    #[test]
+    // Test whether the derivative by each letter recognizes the residual language of that letter.
+    fn test_letter_derivatives() {
+        let empty_char_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            2,
+            vec![(0, 0, 1), (0, 1, 2), (1, 2, 3), (2, 2, 3), (3, 1, 3)],
+            vec![0],
+            vec![3],
+        );
+        let b_then_as_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            2,
+            vec![(0, 2, 1), (1, 1, 1)],
+            vec![0],
+            vec![1],
+        );
+        let as_nd = Automaton::new(
+            AutomatonType::NonDet,
+            1,
+            2,
+            vec![(0, 1, 0)],
+            vec![0],
+            vec![0],
+        );
+
+        let derivatives = empty_char_nd.letter_derivatives();
+        assert_eq!(derivatives.len(), 2);
+        assert!(derivatives[0].equivalent(&b_then_as_nd));
+        assert!(derivatives[1].equivalent(&as_nd));
+        for (i, derivative) in derivatives.iter().enumerate() {
+            assert_eq!(derivative.start, empty_char_nd.derivative(&[i + 1]).start);
+        }
+    }
+
+    #[test]
+    // Test whether running a word reports the position at which the error state is entered.
+    fn test_with_error_state() {
+        let ab_d = Automaton::new(
+            AutomatonType::Det,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 2, 2), (2, 1, 1)],
+            vec![0],
+            vec![2],
+        );
+        let (lexer, error) = ab_d.with_error_state();
+        assert_eq!(error, 3);
+        assert!(lexer.is_complete());
+        assert_eq!(lexer.run(&[1, 2, 1, 2], error), RunOutcome::Accepted);
+        assert_eq!(lexer.run(&[1, 2, 1], error), RunOutcome::Rejected);
+        assert_eq!(lexer.run(&[1, 2, 2, 1], error), RunOutcome::Error(2));
+
+        let mut accept_all_d = Automaton::accept_all(1, 2);
+        accept_all_d.probabilities = Some(vec![0.5, 0.5]);
+        let (complete, error) = accept_all_d.with_error_state();
+        assert_eq!(error, 1);
+        assert_eq!(complete.probabilities, None);
+        assert_eq!(complete.run(&[2, 1], error), RunOutcome::Accepted);
+        assert!(!complete.get_successor_states(&[0]).contains(&error));
+    }
+
+    #[test]
+    // Test whether overriding the start states changes the language to the residual one.
+    fn test_with_start() {
+        let abc_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            3,
+            vec![(0, 1, 1), (1, 2, 2), (2, 3, 3)],
+            vec![0],
+            vec![3],
+        );
+        let from_one = abc_nd.with_start(&[1]).unwrap();
+        assert!(from_one.accepts(&[2, 3]));
+        assert!(!from_one.accepts(&[1, 2, 3]));
+        let from_both = abc_nd.with_start(&[0, 2]).unwrap();
+        let dfa = from_both.determinized(AlgorithmKind::Sequential);
+        assert!(dfa.accepts(&[1, 2, 3]) && dfa.accepts(&[3]));
+        assert!(!dfa.accepts(&[2, 3]));
+        assert!(abc_nd.with_start(&[0, 4]).is_err());
+    }
+
+    #[test]
+    // Test whether the symmetric difference of a language with itself is empty, and of two
+    // languages differing on one word accepts only that word.
+    fn test_symmetric_difference() {
+        let ab_or_b_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 2, 2), (0, 2, 2)],
+            vec![0],
+            vec![2],
+        );
+        let b_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            2,
+            vec![(0, 2, 1)],
+            vec![0],
+            vec![1],
+        );
+        assert!(ab_or_b_nd
+            .symmetric_difference(&ab_or_b_nd)
+            .is_empty_language());
+
+        let difference = ab_or_b_nd.symmetric_difference(&b_nd);
+        let ab_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 2, 2)],
+            vec![0],
+            vec![2],
+        );
+        assert!(difference.accepts(&[1, 2]));
+        assert!(!difference.accepts(&[2]));
+        assert!(difference.equivalent(&ab_nd));
+    }
+
+    #[test]
+    // Test whether shrinking the automaton of words with an `a` 4 letters before the end, with an
+    // isolated state, keeps its exponential blow-up above the ratio.
+    fn test_shrink_to_blowup() {
+        let mut table = vec![(0, 1, 0), (0, 2, 0), (0, 1, 1)];
+        (1..5).for_each(|s| table.extend([(s, 1, s + 1), (s, 2, s + 1)]));
+        table.push((5, 1, 5));
+        let nth_last_nd = Automaton::new(AutomatonType::NonDet, 7, 2, table, vec![0], vec![5]);
+        let ratio = 3.0;
+        let blowup = |aut: &Automaton| {
+            aut.determinized(AlgorithmKind::Sequential).size as f64 / aut.size as f64
+        };
+        assert!(blowup(&nth_last_nd) > ratio);
+
+        let shrunk = nth_last_nd.shrink_to_blowup(ratio);
+        assert!(blowup(&shrunk) > ratio);
+        assert!(shrunk.table.len() < nth_last_nd.table.len());
+        assert_eq!(shrunk.size, 6);
+        assert_eq!(nth_last_nd.shrink_to_blowup(100.0).table, nth_last_nd.table);
+    }
+
+    #[test]
+    // Test whether the subgraph reachable from the middle of a chain holds its tail, renumbered
+    // from the given state.
+    fn test_reachable_subgraph() {
+        let chain_nd = Automaton::new(
+            AutomatonType::NonDet,
+            5,
+            2,
+            vec![(0, 1, 1), (1, 2, 2), (2, 1, 3), (3, 2, 4), (4, 1, 2)],
+            vec![0],
+            vec![4],
+        );
+        let tail = chain_nd.reachable_subgraph(&[2]);
+        assert_eq!(tail.size, 3);
+        assert_eq!(tail.table, vec![(0, 1, 1), (1, 2, 2), (2, 1, 0)]);
+        assert_eq!(tail.start, vec![0]);
+        assert_eq!(tail.end, vec![2]);
+        assert!(tail.equivalent(&chain_nd.derivative(&[1, 2])));
+        assert_eq!(chain_nd.reachable_subgraph(&[0]).size, 5);
+    }
+
+    #[test]
+    // Test whether the complement of a partial DFA and of an NFA, which is determinized first,
+    // accepts exactly the words they reject.
+    fn test_complement() {
+        let partial_d = Automaton::new(
+            AutomatonType::Det,
+            2,
+            2,
+            vec![(0, 1, 1), (1, 2, 0)],
+            vec![0],
+            vec![1],
+        );
+        let ends_in_ab_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 0), (0, 2, 0), (0, 1, 1), (1, 2, 2)],
+            vec![0],
+            vec![2],
+        );
+        for aut in [partial_d, ends_in_ab_nd] {
+            let complement = aut.complement();
+            assert_eq!(complement.automaton_type, AutomatonType::Det);
+            assert!(complement.is_deterministic());
+            let mut words: Vec<Vec<usize>> = vec![vec![]];
+            for _ in 0..4 {
+                for word in &words {
+                    assert_ne!(complement.accepts(word), aut.accepts(word));
+                }
+                words = words
+                    .iter()
+                    .flat_map(|w| (1..3).map(move |a| [&w[..], &[a]].concat()))
+                    .collect();
+            }
+            assert!(complement.complement().equivalent(&aut));
+        }
+    }
+
+    #[test]
+    // Test whether the product of the DFAs of words starting with `a` and of words of even length
+    // accepts the even length words starting with `a`, and is deterministic.
+    fn test_intersection() {
+        let starts_with_a_d = Automaton::new(
+            AutomatonType::Det,
+            2,
+            2,
+            vec![(0, 1, 1), (1, 1, 1), (1, 2, 1)],
+            vec![0],
+            vec![1],
+        );
+        let even_d = Automaton::new(
+            AutomatonType::Det,
+            2,
+            2,
+            vec![(0, 1, 1), (0, 2, 1), (1, 1, 0), (1, 2, 0)],
+            vec![0],
+            vec![0],
+        );
+        let both = starts_with_a_d.intersection(&even_d);
+        assert_eq!(both.size, 4);
+        assert_eq!(both.start, vec![0]);
+        assert_eq!(both.end, vec![2]);
+        assert!(both.is_deterministic());
+        assert!(both.accepts(&[1, 2]));
+        assert!(both.accepts(&[1, 1, 2, 2]));
+        assert!(!both.accepts(&[]));
+        assert!(!both.accepts(&[1]));
+        assert!(!both.accepts(&[2, 1]));
+        assert!(both
+            .intersection(&starts_with_a_d.complement())
+            .is_empty_language());
+    }
+
+    #[test]
+    // Test whether truncating and renumbering in breadth-first order keep the empty char
+    // transitions of the epsilon table, so the words read through them are still accepted.
+    fn test_epsilon_table_renumbering() {
+        let mut epsilon_table_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            1,
+            vec![(1, 1, 2)],
+            vec![0],
+            vec![2],
+        );
+        epsilon_table_nd.epsilon_table = Some(vec![(0, 1)]);
+        assert!(epsilon_table_nd.accepts(&[1]));
+
+        let truncated = epsilon_table_nd.truncate_length(2);
+        assert!(truncated.accepts(&[1]));
+        assert!(!truncated.accepts(&[1, 1]));
+
+        let (bfs, permutation) = epsilon_table_nd.to_bfs_order();
+        assert_eq!(permutation, vec![0, 1, 2]);
+        assert!(bfs.accepts(&[1]));
+
+        epsilon_table_nd.start = vec![1];
+        epsilon_table_nd.table.push((2, 1, 0));
+        let (bfs, permutation) = epsilon_table_nd.to_bfs_order();
+        assert_eq!(permutation, vec![2, 0, 1]);
+        assert!(bfs.accepts(&[1]));
+        assert!(bfs.accepts(&[1, 1, 1]));
+        assert!(!bfs.accepts(&[1, 1]));
+    }
+
+    #[test]
+    // Test whether the constructions keep the alphabet labels both automata share, and letter
+    // classes are labelled with the labels of their letters.
+    fn test_operations_alphabet_labels() {
+        let labels = Some(vec!["x".to_string(), "y".to_string(), "z".to_string()]);
+        let mut x_d = Automaton::new(
+            AutomatonType::Det,
+            2,
+            3,
+            vec![
+                (0, 1, 1),
+                (0, 2, 0),
+                (0, 3, 0),
+                (1, 1, 1),
+                (1, 2, 1),
+                (1, 3, 1),
+            ],
+            vec![0],
+            vec![1],
+        );
+        x_d.alphabet_labels = labels.clone();
+        let unlabelled_d = Automaton::accept_all(1, 3);
+
+        assert_eq!(x_d.union(&x_d).alphabet_labels, labels);
+        assert_eq!(x_d.concat(&x_d).alphabet_labels, labels);
+        assert_eq!(x_d.kleene_star().alphabet_labels, labels);
+        assert_eq!(x_d.intersection(&x_d).alphabet_labels, labels);
+        assert_eq!(x_d.symmetric_difference(&x_d).alphabet_labels, labels);
+        assert_eq!(x_d.union(&unlabelled_d).alphabet_labels, None);
+        assert_eq!(x_d.intersection(&unlabelled_d).alphabet_labels, None);
+
+        let (compressed, classes) = x_d.compress_symbol_classes();
+        assert_eq!(classes, vec![vec![1], vec![2, 3]]);
+        assert_eq!(
+            compressed.alphabet_labels,
+            Some(vec!["x".to_string(), "y|z".to_string()])
+        );
+        assert_eq!(
+            compressed.expand_symbol_classes(&classes).alphabet_labels,
+            None
+        );
+    }
+
+    #[test]
+    // Test whether removing an accept state keeps the priorities of the remaining accept states
+    // aligned with them.
+    fn test_without_states_accept_priority() {
+        let mut priority_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            1,
+            vec![(0, 1, 1), (0, 1, 2), (0, 1, 3)],
+            vec![0],
+            vec![3, 1, 2],
+        );
+        priority_nd.accept_priority = Some(vec![7, 5, 2]);
+        let without = priority_nd.without_states(&HashSet::from([1]));
+        assert_eq!(without.end, vec![2, 1]);
+        assert_eq!(without.accept_priority, Some(vec![7, 2]));
+    }
+}
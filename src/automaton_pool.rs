@@ -0,0 +1,93 @@
+use std::{
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::automaton::{AlgorithmKind, Automaton};
+
+/// Determinization job sent to the workers of a pool, with the channel to reply on.
+struct DeterminizeJob {
+    aut: Automaton,
+    reply_tx: Sender<Automaton>,
+}
+
+/// Pool of persistent worker threads determinizing automata from a shared job queue.
+/// Each automaton is determinized sequentially by a single worker, so that many small
+/// determinizations run in parallel without spawning threads for each of them.
+pub struct DeterminizerPool {
+    job_tx: Option<Sender<DeterminizeJob>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl DeterminizerPool {
+    /// Create a pool of `n_threads` worker threads.
+    pub fn new(n_threads: usize) -> DeterminizerPool {
+        let (job_tx, job_rx): (Sender<DeterminizeJob>, Receiver<DeterminizeJob>) = channel();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        let workers = (0..n_threads.max(1))
+            .map(|_| {
+                let job_rx = Arc::clone(&job_rx);
+                thread::spawn(move || determinizer_pool_worker(job_rx))
+            })
+            .collect();
+        DeterminizerPool {
+            job_tx: Some(job_tx),
+            workers,
+        }
+    }
+
+    /// Determinize an automaton on a worker of the pool, waiting for the result.
+    pub fn determinize(&self, aut: &Automaton) -> Automaton {
+        self.submit(aut).recv().unwrap()
+    }
+
+    /// Determinize several automata on the workers of the pool, returning the results in order.
+    pub fn determinize_all(&self, auts: &[Automaton]) -> Vec<Automaton> {
+        let replies: Vec<Receiver<Automaton>> = auts.iter().map(|aut| self.submit(aut)).collect();
+        replies
+            .into_iter()
+            .map(|reply_rx| reply_rx.recv().unwrap())
+            .collect()
+    }
+
+    /// Queue an automaton to determinize, and get the channel the result will be sent on.
+    fn submit(&self, aut: &Automaton) -> Receiver<Automaton> {
+        let (reply_tx, reply_rx) = channel();
+        self.job_tx
+            .as_ref()
+            .unwrap()
+            .send(DeterminizeJob {
+                aut: aut.clone(),
+                reply_tx,
+            })
+            .unwrap();
+        reply_rx
+    }
+}
+
+impl Drop for DeterminizerPool {
+    /// Close the job queue and wait for the workers to finish.
+    fn drop(&mut self) {
+        drop(self.job_tx.take());
+        self.workers.drain(..).for_each(|w| w.join().unwrap());
+    }
+}
+
+/// Worker thread behaviour - Determinize jobs until the job queue is closed.
+fn determinizer_pool_worker(job_rx: Arc<Mutex<Receiver<DeterminizeJob>>>) {
+    loop {
+        let job = job_rx.lock().unwrap().recv();
+        match job {
+            Ok(job) => {
+                // The caller may have stopped waiting for the result.
+                let _ = job
+                    .reply_tx
+                    .send(job.aut.determinized(AlgorithmKind::Sequential));
+            }
+            Err(_) => break,
+        }
+    }
+}
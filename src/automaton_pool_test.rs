@@ -0,0 +1,32 @@
+#[cfg(test)]
+mod tests {
+    use crate::automaton::{AlgorithmKind, Automaton, AutomatonType};
+    use crate::automaton_pool::DeterminizerPool;
+    use crate::transition_graphs::{get_buffer_and_stack_aut, get_random_nfa};
+
+    #[test]
+    // Test whether automata determinized through one pool match their one-shot determinization.
+    fn test_pool_matches_one_shot() {
+        let mut auts: Vec<Automaton> = (0..8).map(|i| get_random_nfa(6, 2, 14, i)).collect();
+        auts.push(get_buffer_and_stack_aut(2, 2));
+        auts.push(Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 1, 2)],
+            vec![0],
+            vec![2],
+        ));
+        let pool = DeterminizerPool::new(4);
+
+        let determinized = pool.determinize_all(&auts);
+        for (aut, dfa) in auts.iter().zip(determinized.iter()) {
+            let one_shot = aut.determinized(AlgorithmKind::Sequential);
+            assert_eq!(dfa.size, one_shot.size);
+            assert_eq!(dfa.table, one_shot.table);
+            assert_eq!(dfa.end, one_shot.end);
+        }
+        let single = pool.determinize(&auts[0]);
+        assert_eq!(single.table, determinized[0].table);
+    }
+}
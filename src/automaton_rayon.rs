@@ -0,0 +1,84 @@
+use dashmap::{mapref::entry::Entry, DashMap};
+use fasthash::xx::Hasher64;
+use rayon::prelude::*;
+use std::{
+    hash::BuildHasherDefault,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{automaton::Automaton, state_set::StateSet};
+
+type DashMapXX<K, V> = DashMap<K, V, BuildHasherDefault<Hasher64>>;
+type Transition = (usize, usize, usize);
+/// Transitions, accept states and newly discovered sets found by exploring one set.
+type Explored<S> = (Vec<Transition>, Vec<usize>, Vec<(usize, S)>);
+
+////////////////
+// Algorithms //
+////////////////
+
+/// Rabin-Scott/superset construction exploring the frontier in waves - Every set of a breadth
+/// first level is explored in parallel on the rayon thread pool, and the sets first discovered
+/// while exploring a level make up the next one.
+/// Returns: (transitions vector, number of states, start states, end states).
+pub fn rabin_scott_rayon<S: StateSet>(
+    aut: &Automaton,
+) -> (Vec<Transition>, usize, Vec<usize>, Vec<usize>) {
+    let transition_arr = aut.get_transition_array();
+    let num_map: DashMapXX<S::Key, usize> = DashMapXX::default();
+    // States are numbered densely in the order they are discovered, the start state being 0.
+    let next_id = AtomicUsize::new(1);
+
+    let mut start_state = S::default();
+    aut.start
+        .iter()
+        .for_each(|s| aut.add_state(&transition_arr, &mut start_state, *s));
+    let mut accept_states: Vec<usize> = match aut.end.iter().any(|s| start_state.bit_at(s)) {
+        true => vec![0],
+        false => vec![],
+    };
+    num_map.insert(start_state.to_key(), 0);
+
+    let mut transitions: Vec<Transition> = Vec::new();
+    let mut level: Vec<(usize, S)> = vec![(0, start_state)];
+    while !level.is_empty() {
+        let explored: Vec<Explored<S>> = level
+            .par_iter()
+            .map(|(id_next, next)| {
+                let mut local_transitions = Vec::with_capacity(aut.alphabet);
+                let mut local_accepts = Vec::new();
+                let mut discovered = Vec::new();
+                let next_seq = next.get_seq();
+                for a in 1..aut.alphabet + 1 {
+                    let mut new_s = S::default();
+                    next_seq.iter().for_each(|s| {
+                        transition_arr[a][*s]
+                            .iter()
+                            .for_each(|t| aut.add_state(&transition_arr, &mut new_s, *t));
+                    });
+                    let id_new = match num_map.entry(new_s.to_key()) {
+                        Entry::Occupied(e) => *e.get(),
+                        Entry::Vacant(e) => {
+                            let id_new = *e.insert(next_id.fetch_add(1, Ordering::Relaxed));
+                            if aut.end.iter().any(|s| new_s.bit_at(s)) {
+                                local_accepts.push(id_new);
+                            }
+                            discovered.push((id_new, new_s));
+                            id_new
+                        }
+                    };
+                    local_transitions.push((*id_next, a, id_new));
+                }
+                (local_transitions, local_accepts, discovered)
+            })
+            .collect();
+
+        level = Vec::new();
+        for (local_transitions, local_accepts, discovered) in explored {
+            transitions.extend(local_transitions);
+            accept_states.extend(local_accepts);
+            level.extend(discovered);
+        }
+    }
+    (transitions, num_map.len(), vec![0], accept_states)
+}
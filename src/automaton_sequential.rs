@@ -1,16 +1,22 @@
 use fasthash::xx::Hasher64;
 use std::{
     cmp::{Ordering, Reverse},
-    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    collections::{hash_map::Entry, BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque},
     hash::BuildHasherDefault,
+    io,
+    sync::atomic::{AtomicBool, Ordering as AtomicOrdering},
 };
 
 use crate::{
-    automaton::Automaton,
+    automaton::{Automaton, DeterminizeStats, DeterminizeStep},
+    state_set::StateSet,
+    trie::NodeTrie,
     ubig::{CompressedUbig, Ubig},
 };
 
-type HashMapXX<K, V> = HashMap<K, V, BuildHasherDefault<Hasher64>>;
+pub(crate) type HashMapXX<K, V> = HashMap<K, V, BuildHasherDefault<Hasher64>>;
+/// Determinized automaton as (transitions vector, number of states, start states, end states).
+pub(crate) type Determinization = (Vec<(usize, usize, usize)>, usize, Vec<usize>, Vec<usize>);
 
 impl Automaton {
     /// Replace an element in a queue with a new element, and append the rest to the queue.
@@ -107,20 +113,151 @@ impl Automaton {
     }
 }
 
+/// Queue of subsets of states waiting to be explored by the superset construction.
+pub trait Frontier<S: StateSet = Ubig> {
+    fn push_back(&mut self, set: S) -> io::Result<()>;
+    fn pop_front(&mut self) -> io::Result<Option<S>>;
+}
+
+impl<S: StateSet> Frontier<S> for VecDeque<S> {
+    fn push_back(&mut self, set: S) -> io::Result<()> {
+        VecDeque::push_back(self, set);
+        Ok(())
+    }
+
+    fn pop_front(&mut self) -> io::Result<Option<S>> {
+        Ok(VecDeque::pop_front(self))
+    }
+}
+
+/// In-memory frontier recording the statistics of the subsets pushed onto it. Each subset is
+/// pushed once, when it is first mapped to a DFA state.
+#[derive(Default)]
+pub struct StatsFrontier {
+    queue: VecDeque<Ubig>,
+    pub stats: DeterminizeStats,
+}
+
+impl Frontier for StatsFrontier {
+    fn push_back(&mut self, set: Ubig) -> io::Result<()> {
+        self.stats.subsets += 1;
+        self.stats.subset_bytes += set.clone().compress().cnum.len();
+        self.queue.push_back(set);
+        self.stats.peak_frontier = self.stats.peak_frontier.max(self.queue.len());
+        Ok(())
+    }
+
+    fn pop_front(&mut self) -> io::Result<Option<Ubig>> {
+        Ok(self.queue.pop_front())
+    }
+}
+
+/// Frontier stopping the superset construction once cancelled, keeping the unexplored subsets
+/// in the inner frontier.
+pub struct CancellableFrontier<'a, F: Frontier> {
+    pub inner: F,
+    cancel: &'a AtomicBool,
+}
+
+impl<'a, F: Frontier> CancellableFrontier<'a, F> {
+    pub fn new(inner: F, cancel: &'a AtomicBool) -> Self {
+        CancellableFrontier { inner, cancel }
+    }
+}
+
+impl<F: Frontier> Frontier for CancellableFrontier<'_, F> {
+    fn push_back(&mut self, set: Ubig) -> io::Result<()> {
+        self.inner.push_back(set)
+    }
+
+    fn pop_front(&mut self) -> io::Result<Option<Ubig>> {
+        match self.cancel.load(AtomicOrdering::Relaxed) {
+            true => Ok(None),
+            false => self.inner.pop_front(),
+        }
+    }
+}
+
 /// Rabin Scott Superset Construction Algorithm - Used for determinization of NFAs.
 /// Returns: (transitions vector, number of states, start states, end states).
-pub fn rabin_scott_seq(
+pub fn rabin_scott_seq<S: StateSet>(
     aut: &Automaton,
 ) -> (Vec<(usize, usize, usize)>, usize, Vec<usize>, Vec<usize>) {
+    // An in-memory frontier cannot fail.
+    rabin_scott_seq_with(aut, &mut VecDeque::<S>::new()).unwrap()
+}
+
+/// Rabin Scott Superset Construction Algorithm, exploring subsets from the given frontier.
+pub fn rabin_scott_seq_with<S: StateSet, F: Frontier<S>>(
+    aut: &Automaton,
+    frontier: &mut F,
+) -> io::Result<Determinization> {
+    let mut num_mapper: HashMapXX<S::Key, usize> = HashMapXX::default();
+    rabin_scott_seq_mapped(aut, frontier, &mut num_mapper, |_| {})
+}
+
+/// Rabin Scott Superset Construction Algorithm, numbering subsets with a `NodeTrie` keyed on
+/// their sorted states rather than a map of compressed subsets.
+pub fn rabin_scott_seq_trie(aut: &Automaton) -> Determinization {
+    // An in-memory frontier cannot fail.
+    rabin_scott_seq_mapped(
+        aut,
+        &mut VecDeque::<Ubig>::new(),
+        &mut NodeTrie::new(),
+        |_| {},
+    )
+    .unwrap()
+}
+
+/// Map from the subsets discovered by the superset construction to their DFA state ids.
+pub trait NumMapper<S: StateSet> {
+    /// Get the id of a subset, numbering it with the next id if it is new. Returns whether it
+    /// is new.
+    fn get_or_insert(&mut self, set: &S) -> (usize, bool);
+    fn len(&self) -> usize;
+}
+
+impl<S: StateSet> NumMapper<S> for HashMapXX<S::Key, usize> {
+    fn get_or_insert(&mut self, set: &S) -> (usize, bool) {
+        let id = HashMap::len(self);
+        match self.entry(set.to_key()) {
+            Entry::Occupied(e) => (*e.get(), false),
+            Entry::Vacant(e) => (*e.insert(id), true),
+        }
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+/// Subset transition explored by the superset construction, along with the DFA state ids of its
+/// subsets.
+struct SubsetStep<'a, S: StateSet> {
+    source: usize,
+    source_set: &'a S,
+    letter: usize,
+    target: usize,
+    target_set: &'a S,
+    /// Whether the target subset was first discovered in this step.
+    discovered: bool,
+}
+
+/// Rabin Scott Superset Construction Algorithm, exploring subsets from the given frontier and
+/// numbering them with the given mapper. Calls `on_step` with every subset transition explored.
+fn rabin_scott_seq_mapped<S: StateSet, F: Frontier<S>, M: NumMapper<S>>(
+    aut: &Automaton,
+    frontier: &mut F,
+    num_mapper: &mut M,
+    mut on_step: impl FnMut(SubsetStep<S>),
+) -> io::Result<Determinization> {
     // Rabin Scott Superset Construction Algorithm
     let mut transitions: Vec<(usize, usize, usize)> = Vec::new(); // All DFA transitions
     let mut accept_states: Vec<usize> = Vec::new(); // All accept states
-    let mut num_mapper: HashMapXX<CompressedUbig, usize> = HashMapXX::default();
-    let mut frontier: VecDeque<Ubig> = VecDeque::new();
 
     // Select start state from all start states in the non deterministic automata.
     let transition_arr = aut.get_transition_array();
-    let mut start_state = Ubig::new();
+    let mut start_state = S::default();
     (&aut.start)
         .into_iter()
         .for_each(|s| aut.add_state(&transition_arr, &mut start_state, *s));
@@ -130,50 +267,195 @@ pub fn rabin_scott_seq(
             break;
         }
     }
-    num_mapper.insert(start_state.clone().compress(), num_mapper.len());
-    frontier.push_back(start_state.clone());
+    num_mapper.get_or_insert(&start_state);
+    frontier.push_back(start_state)?;
 
     // Graph exploration - Depth-first search
-    while let Some(next) = frontier.pop_front() {
-        (1..aut.alphabet + 1).for_each(|a| {
-            let mut new_s = Ubig::new();
-            next.get_seq().into_iter().for_each(|s| {
-                (&transition_arr[a][s]).into_iter().for_each(|t| {
+    while let Some(next) = frontier.pop_front()? {
+        let (next_id, _) = num_mapper.get_or_insert(&next);
+        let next_seq = next.get_seq();
+        for a in 1..aut.alphabet + 1 {
+            let mut new_s = S::default();
+            next_seq.iter().for_each(|s| {
+                transition_arr[a][*s].iter().for_each(|t| {
                     aut.add_state(&transition_arr, &mut new_s, *t);
                 })
             });
-            let compressed_new_s = new_s.clone().compress();
-
-            if !num_mapper.contains_key(&compressed_new_s) {
-                num_mapper.insert(compressed_new_s.clone(), num_mapper.len());
-                for s in &aut.end {
-                    if new_s.bit_at(s) {
-                        accept_states.push(num_mapper.len() - 1);
-                        break;
+
+            // Subsets are only looked up once, and new ones are moved onto the frontier.
+            let (new_id, is_new) = num_mapper.get_or_insert(&new_s);
+            on_step(SubsetStep {
+                source: next_id,
+                source_set: &next,
+                letter: a,
+                target: new_id,
+                target_set: &new_s,
+                discovered: is_new,
+            });
+            if is_new {
+                if aut.end.iter().any(|s| new_s.bit_at(s)) {
+                    accept_states.push(new_id);
+                }
+                frontier.push_back(new_s)?;
+            }
+            transitions.push((next_id, a, new_id));
+        }
+    }
+    Ok((transitions, num_mapper.len(), vec![0], accept_states))
+}
+
+/// DFA under construction by the fused determinization, where explored states with the same
+/// acceptance and the same targets get merged.
+#[derive(Default)]
+struct FusedDfa {
+    /// State of every discovered subset - Merged subsets stay, so reaching them again resolves to
+    /// their state instead of exploring them anew.
+    num_mapper: HashMapXX<CompressedUbig, usize>,
+    /// Targets of each DFA state by letter, empty until it is explored or once it is merged.
+    rows: Vec<Vec<usize>>,
+    accepting: Vec<bool>,
+    /// State each DFA state was merged into, or itself.
+    alias: Vec<usize>,
+}
+
+impl FusedDfa {
+    /// Get the state of a subset, pushing the subset onto the frontier if it is new.
+    fn discover(&mut self, aut: &Automaton, set: Ubig, frontier: &mut VecDeque<Ubig>) -> usize {
+        let compressed = set.clone().compress();
+        if let Some(id) = self.num_mapper.get(&compressed) {
+            return *id;
+        }
+        let id = self.num_mapper.len();
+        self.num_mapper.insert(compressed, id);
+        self.rows.push(Vec::new());
+        self.accepting.push(aut.end.iter().any(|s| set.bit_at(s)));
+        self.alias.push(id);
+        frontier.push_back(set);
+        id
+    }
+
+    /// Get the state a state was last merged into.
+    fn find(&self, mut s: usize) -> usize {
+        while self.alias[s] != s {
+            s = self.alias[s];
+        }
+        s
+    }
+
+    /// Merge explored states with the same acceptance and the same targets until none are left,
+    /// as merging states can make the states leading to them share their targets.
+    fn merge_equivalent(&mut self) {
+        let mut merged = true;
+        while merged {
+            merged = false;
+            let mut signatures: HashMap<(bool, Vec<usize>), usize> = HashMap::new();
+            for s in 0..self.rows.len() {
+                if self.alias[s] != s || self.rows[s].is_empty() {
+                    continue;
+                }
+                let targets = self.rows[s].iter().map(|e| self.find(*e)).collect();
+                match signatures.entry((self.accepting[s], targets)) {
+                    Entry::Occupied(e) => {
+                        self.alias[s] = *e.get();
+                        self.rows[s] = Vec::new();
+                        merged = true;
+                    }
+                    Entry::Vacant(e) => {
+                        e.insert(s);
                     }
                 }
-                frontier.push_back(new_s.clone());
             }
-            let next_compressed = next.clone().compress();
-            transitions.push((
-                *num_mapper.get(&next_compressed).unwrap(),
-                a,
-                *num_mapper.get(&compressed_new_s).unwrap(),
-            ));
-        });
+        }
     }
-    return (transitions, num_mapper.len(), vec![0], accept_states);
+}
+
+/// Rabin Scott Superset Construction Algorithm, merging the explored DFA states with the same
+/// acceptance and the same targets every `merge_interval` explored states, and once at the end.
+/// Returns: (transitions vector, number of states, start states, end states).
+pub fn rabin_scott_fused(aut: &Automaton, merge_interval: usize) -> Determinization {
+    let mut dfa = FusedDfa::default();
+    let mut frontier: VecDeque<Ubig> = VecDeque::new();
+    let transition_arr = aut.get_transition_array();
+    dfa.discover(aut, aut.get_start_set(&transition_arr), &mut frontier);
+
+    let mut explored = 0;
+    while let Some(next) = frontier.pop_front() {
+        let id = dfa.discover(aut, next.clone(), &mut frontier);
+        dfa.rows[id] = (1..aut.alphabet + 1)
+            .map(|a| {
+                let new_s = aut.get_next_set(&transition_arr, &next, a);
+                dfa.discover(aut, new_s, &mut frontier)
+            })
+            .collect();
+        explored += 1;
+        if explored % merge_interval == 0 {
+            dfa.merge_equivalent();
+        }
+    }
+    dfa.merge_equivalent();
+
+    // Renumber the remaining states in order.
+    let kept: Vec<usize> = (0..dfa.alias.len())
+        .filter(|s| dfa.alias[*s] == *s)
+        .collect();
+    let mut new_ids = vec![0; dfa.alias.len()];
+    kept.iter()
+        .enumerate()
+        .for_each(|(new, old)| new_ids[*old] = new);
+    let resolve = |s: usize| new_ids[dfa.find(s)];
+    let mut transitions: Vec<(usize, usize, usize)> = Vec::new();
+    let mut accept_states: Vec<usize> = Vec::new();
+    for s in &kept {
+        for (a, e) in dfa.rows[*s].iter().enumerate() {
+            transitions.push((new_ids[*s], a + 1, resolve(*e)));
+        }
+        if dfa.accepting[*s] {
+            accept_states.push(new_ids[*s]);
+        }
+    }
+    (transitions, kept.len(), vec![resolve(0)], accept_states)
+}
+
+/// Rabin Scott Superset Construction Algorithm, recording every subset transition explored.
+pub fn rabin_scott_trace(aut: &Automaton) -> Vec<DeterminizeStep> {
+    let mut steps = vec![];
+    let mut num_mapper: HashMapXX<CompressedUbig, usize> = HashMapXX::default();
+    // An in-memory frontier cannot fail.
+    rabin_scott_seq_mapped(aut, &mut VecDeque::<Ubig>::new(), &mut num_mapper, |step| {
+        steps.push(DeterminizeStep {
+            source: step.source,
+            source_set: step.source_set.get_seq(),
+            letter: step.letter,
+            target: step.target,
+            target_set: step.target_set.get_seq(),
+            discovered: step.discovered,
+        })
+    })
+    .unwrap();
+    steps
 }
 
 /// Hopcroft algorithm for minimization of a DFA.
 /// Returns a map of what state is in which leading partition, and the number of partitions.
 pub fn hopcroft_algo(aut: &Automaton) -> (HashMap<usize, usize>, usize) {
+    hopcroft_algo_with(aut, |_| {})
+}
+
+/// Hopcroft algorithm for minimization of a DFA, passing the partitions to `on_splitter` after
+/// each splitter is processed. Partitions may be empty.
+pub fn hopcroft_algo_with<F: FnMut(&[Vec<usize>])>(
+    aut: &Automaton,
+    mut on_splitter: F,
+) -> (HashMap<usize, usize>, usize) {
     let finals: HashSet<usize> = aut.end.clone().into_iter().collect();
+    // Partitions are kept sorted, as splitting them relies on it.
     let mut p: Vec<Vec<usize>> = Vec::from_iter(vec![
         (0..aut.size)
             .filter(|i| !finals.contains(i))
             .collect::<Vec<usize>>(),
-        aut.end.clone(),
+        (0..aut.size)
+            .filter(|i| finals.contains(i))
+            .collect::<Vec<usize>>(),
     ]);
     let mut q = VecDeque::from(p.clone());
     let mut state_partition_map = (0..aut.size)
@@ -184,7 +466,8 @@ pub fn hopcroft_algo(aut: &Automaton) -> (HashMap<usize, usize>, usize) {
     while let Some(set) = q.pop_front() {
         for c in 1..aut.alphabet + 1 {
             let rs = Automaton::get_set_from_transitions(&rev_arr, &set, c);
-            let potential_partitions: HashSet<usize> = (&rs)
+            // Split partitions in order so that partition ids do not depend on hashing.
+            let potential_partitions: BTreeSet<usize> = (&rs)
                 .into_iter()
                 .map(|i| state_partition_map.get(*i).unwrap().clone())
                 .collect();
@@ -206,16 +489,18 @@ pub fn hopcroft_algo(aut: &Automaton) -> (HashMap<usize, usize>, usize) {
                 }
             });
         }
+        on_splitter(&p);
     }
 
-    // Convert partition into map from initial state to partitioned state
+    // Convert partition into map from initial state to partitioned state. The initial accepting
+    // or non accepting partition is empty when all states are on one side, and gets no index.
     let mut ret_map: HashMap<usize, usize> = HashMap::new();
     let mut index = 0;
-    p.iter().for_each(|next| {
+    p.iter().filter(|next| !next.is_empty()).for_each(|next| {
         next.iter().for_each(|s| {
             ret_map.insert(*s, index);
         });
         index += 1;
     });
-    return (ret_map, p.len());
+    (ret_map, index)
 }
@@ -0,0 +1,172 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, ErrorKind},
+};
+
+use crate::{
+    automaton::{Automaton, AutomatonType},
+    ubig::Ubig,
+};
+
+/// Superset construction of an automaton that can be run a few subsets at a time, and saved to
+/// bytes between runs to resume it later. Subsets are numbered in the order they are discovered,
+/// as in the sequential determinization, so a completed session gives the same DFA.
+/// The automaton is not part of the session, and the same one must be given on every run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeterminizeSession {
+    num_mapper: HashMap<Vec<usize>, usize>,
+    frontier: VecDeque<Vec<usize>>,
+    table: Vec<(usize, usize, usize)>,
+    end: Vec<usize>,
+}
+
+impl DeterminizeSession {
+    /// Start a session determinizing the automaton, with only its start set discovered.
+    pub fn new(aut: &Automaton) -> DeterminizeSession {
+        let mut session = DeterminizeSession {
+            num_mapper: HashMap::new(),
+            frontier: VecDeque::new(),
+            table: vec![],
+            end: vec![],
+        };
+        let start_set = aut.get_start_set(&aut.get_transition_array()).get_seq();
+        session.discover(aut, start_set);
+        session
+    }
+
+    /// Check whether every discovered subset has been explored.
+    pub fn is_done(&self) -> bool {
+        self.frontier.is_empty()
+    }
+
+    /// Get the number of DFA states discovered so far.
+    pub fn discovered(&self) -> usize {
+        self.num_mapper.len()
+    }
+
+    /// Explore at most `max_subsets` subsets of the frontier. Returns whether the session is done.
+    pub fn run(&mut self, aut: &Automaton, max_subsets: usize) -> bool {
+        let transition_arr = aut.get_transition_array();
+        for _ in 0..max_subsets {
+            let Some(next) = self.frontier.pop_front() else {
+                break;
+            };
+            let source = self.num_mapper[&next];
+            let mut next_set = Ubig::new();
+            next.iter().for_each(|s| next_set.set_to(s, true));
+            for a in 1..aut.alphabet + 1 {
+                let new_set = aut.get_next_set(&transition_arr, &next_set, a).get_seq();
+                let target = self.discover(aut, new_set);
+                self.table.push((source, a, target));
+            }
+        }
+        self.is_done()
+    }
+
+    /// Run the session to completion and get the determinized automaton.
+    pub fn finish(mut self, aut: &Automaton) -> Automaton {
+        while !self.run(aut, usize::MAX) {}
+        let mut ret = Automaton::new(
+            AutomatonType::Det,
+            self.num_mapper.len(),
+            aut.alphabet,
+            self.table,
+            vec![0],
+            self.end,
+        );
+        ret.alphabet_labels = aut.alphabet_labels.clone();
+        ret
+    }
+
+    /// Get the id of a subset, numbering it and pushing it onto the frontier if it is new.
+    fn discover(&mut self, aut: &Automaton, set: Vec<usize>) -> usize {
+        if let Some(id) = self.num_mapper.get(&set) {
+            return *id;
+        }
+        let id = self.num_mapper.len();
+        if aut.end.iter().any(|s| set.binary_search(s).is_ok()) {
+            self.end.push(id);
+        }
+        self.num_mapper.insert(set.clone(), id);
+        self.frontier.push_back(set);
+        id
+    }
+
+    /// Write the session as little-endian `u64` sections, each prefixed with its length: the
+    /// subsets in id order, the ids of the unexplored subsets, the accept states, then the
+    /// transitions as `source letter target` triples.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let empty = vec![];
+        let mut subsets: Vec<&Vec<usize>> = vec![&empty; self.num_mapper.len()];
+        self.num_mapper
+            .iter()
+            .for_each(|(set, id)| subsets[*id] = set);
+        let frontier: Vec<usize> = self
+            .frontier
+            .iter()
+            .map(|set| self.num_mapper[set])
+            .collect();
+        let table: Vec<usize> = self
+            .table
+            .iter()
+            .flat_map(|(s, a, e)| [*s, *a, *e])
+            .collect();
+
+        let mut bytes = vec![];
+        let mut write = |values: &[usize]| {
+            bytes.extend((values.len() as u64).to_le_bytes());
+            values
+                .iter()
+                .for_each(|v| bytes.extend((*v as u64).to_le_bytes()));
+        };
+        write(&[subsets.len()]);
+        subsets.into_iter().for_each(|set| write(set));
+        write(&frontier);
+        write(&self.end);
+        write(&table);
+        bytes
+    }
+
+    /// Read a session written by `to_bytes`. Fails if the bytes are truncated or inconsistent.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<DeterminizeSession> {
+        let invalid = |msg: &str| io::Error::new(ErrorKind::InvalidData, msg);
+        let mut words = bytes
+            .chunks(8)
+            .map(|chunk| <[u8; 8]>::try_from(chunk).map(|w| u64::from_le_bytes(w) as usize));
+        let mut read = || -> io::Result<Vec<usize>> {
+            let mut next = || {
+                words
+                    .next()
+                    .and_then(Result::ok)
+                    .ok_or_else(|| invalid("Session bytes are truncated!"))
+            };
+            let len = next()?;
+            (0..len).map(|_| next()).collect()
+        };
+
+        let count = match read()?[..] {
+            [count] => count,
+            _ => return Err(invalid("Session bytes have no subset count!")),
+        };
+        let subsets: Vec<Vec<usize>> = (0..count).map(|_| read()).collect::<io::Result<_>>()?;
+        let frontier = read()?;
+        let end = read()?;
+        let table = read()?;
+        if table.len() % 3 != 0
+            || frontier.iter().chain(&end).any(|id| *id >= count)
+            || table.chunks(3).any(|t| t[0] >= count || t[2] >= count)
+        {
+            return Err(invalid("Session bytes are inconsistent!"));
+        }
+        Ok(DeterminizeSession {
+            frontier: frontier.iter().map(|id| subsets[*id].clone()).collect(),
+            num_mapper: subsets
+                .into_iter()
+                .enumerate()
+                .map(|(id, set)| (set, id))
+                .collect(),
+            table: table.chunks(3).map(|t| (t[0], t[1], t[2])).collect(),
+            end,
+        })
+    }
+}
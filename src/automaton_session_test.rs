@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use crate::automaton::{AlgorithmKind, Automaton, AutomatonType};
+    use crate::automaton_session::DeterminizeSession;
+    use crate::transition_graphs::get_buffer_and_stack_aut;
+
+    #[test]
+    // Test whether a session saved halfway, then restored and completed, matches the one-shot
+    // sequential determinization.
+    fn test_session_round_trip() {
+        let bns = get_buffer_and_stack_aut(2, 3);
+        let full = bns.determinized(AlgorithmKind::Sequential);
+
+        let mut session = DeterminizeSession::new(&bns);
+        assert!(!session.run(&bns, full.size / 2));
+        assert!(session.discovered() < full.size);
+        let bytes = session.to_bytes();
+        let restored = DeterminizeSession::from_bytes(&bytes).unwrap();
+        assert_eq!(restored, session);
+
+        let dfa = restored.finish(&bns);
+        assert_eq!(dfa.size, full.size);
+        assert_eq!(dfa.table, full.table);
+        assert_eq!(dfa.start, full.start);
+        assert_eq!(dfa.end, full.end);
+    }
+
+    #[test]
+    // Test whether truncated or inconsistent session bytes are rejected.
+    fn test_session_invalid_bytes() {
+        let sinkhole_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 1, 2)],
+            vec![0],
+            vec![2],
+        );
+        let bytes = DeterminizeSession::new(&sinkhole_nd).to_bytes();
+        assert!(DeterminizeSession::from_bytes(&bytes[..bytes.len() - 4]).is_err());
+        assert!(DeterminizeSession::from_bytes(&[]).is_err());
+
+        // The subset count and the start subset come before the id of the unexplored start.
+        let mut inconsistent = bytes.clone();
+        inconsistent[40] = 7;
+        assert!(DeterminizeSession::from_bytes(&inconsistent).is_err());
+
+        // The table comes last, ending with the target of the last transition explored.
+        let mut session = DeterminizeSession::new(&sinkhole_nd);
+        session.run(&sinkhole_nd, 1);
+        let mut bad_target = session.to_bytes();
+        let last = bad_target.len() - 8;
+        bad_target[last..].copy_from_slice(&99u64.to_le_bytes());
+        assert!(DeterminizeSession::from_bytes(&session.to_bytes()).is_ok());
+        assert!(DeterminizeSession::from_bytes(&bad_target).is_err());
+    }
+
+    #[test]
+    // Test whether a finished session keeps the alphabet labels of the automaton.
+    fn test_session_alphabet_labels() {
+        let mut labelled_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            1,
+            vec![(0, 1, 1)],
+            vec![0],
+            vec![1],
+        );
+        labelled_nd.alphabet_labels = Some(vec!["x".to_string()]);
+        let dfa = DeterminizeSession::new(&labelled_nd).finish(&labelled_nd);
+        assert_eq!(dfa.alphabet_labels, labelled_nd.alphabet_labels);
+    }
+}
@@ -1,6 +1,16 @@
 #[cfg(test)]
 mod tests {
-    use crate::automaton::{AlgorithmKind, Automaton, AutomatonType};
+    use std::collections::{HashMap, VecDeque};
+    use std::io;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use crate::automaton::{
+        AlgorithmKind, Automaton, AutomatonType, DeterminizeStats, DeterminizeStep, PipelineTiming,
+        ResumableDeterminization,
+    };
+    use crate::automaton_sequential::{rabin_scott_fused, rabin_scott_seq, Frontier};
+    use crate::transition_graphs::{get_buffer_and_stack_aut, get_random_nfa};
+    use crate::ubig::Ubig;
 
     impl Automaton {
         pub fn order_transitions(mut self) -> Self {
@@ -27,9 +37,10 @@ mod tests {
     }
 
     const NUM_THREADS: usize = 4;
-    const KINDS: [AlgorithmKind; 2] = [
+    const KINDS: [AlgorithmKind; 3] = [
         AlgorithmKind::Sequential,
         AlgorithmKind::Multithreaded(NUM_THREADS),
+        AlgorithmKind::Rayon,
     ];
 
     #[test]
@@ -59,6 +70,88 @@ mod tests {
         });
     }
 
+    #[test]
+    // Test whether the fast path for deterministic NFAs gives the same DFA as the superset
+    // construction.
+    fn test_determinization_fast_path() {
+        let partial_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            2,
+            vec![(0, 1, 1), (1, 1, 2), (1, 2, 0), (2, 2, 2), (3, 1, 0)],
+            vec![0],
+            vec![2],
+        );
+        assert!(partial_nd.is_deterministic());
+        let (table, size, start, end) = rabin_scott_seq::<Ubig>(&partial_nd);
+        let full = Automaton::new(AutomatonType::Det, size, 2, table, start, end);
+        KINDS.iter().for_each(|k| {
+            let fast = partial_nd.determinized(*k);
+            assert_eq!(fast.automaton_type, AutomatonType::Det);
+            assert_eq!(fast.size, full.size);
+            assert_eq!(fast.table, full.table);
+            assert_eq!(fast.end, full.end);
+        });
+    }
+
+    #[test]
+    // Test whether spilling the frontier to disk gives the same DFA as in-memory determinization.
+    fn test_determinization_spilling() {
+        let buffer_and_stack = get_buffer_and_stack_aut(2, 2);
+        let in_memory = buffer_and_stack.determinized(AlgorithmKind::Sequential);
+        for limit in [1, 2, 5] {
+            let spilled = buffer_and_stack.determinized_spilling(limit).unwrap();
+            assert_eq!(spilled.size, in_memory.size);
+            assert_eq!(spilled.table, in_memory.table);
+            assert_eq!(spilled.end, in_memory.end);
+        }
+    }
+
+    #[test]
+    // Test whether determinization passes its self check on the determinization fixtures.
+    fn test_determinized_checked() {
+        let fixtures = vec![
+            Automaton::new(AutomatonType::NonDet, 1, 2, vec![], vec![0], vec![0]),
+            Automaton::new(
+                AutomatonType::NonDet,
+                3,
+                2,
+                vec![(0, 1, 1), (1, 1, 2)],
+                vec![0],
+                vec![2],
+            ),
+            Automaton::new(
+                AutomatonType::NonDet,
+                2,
+                1,
+                vec![(0, 1, 0), (0, 1, 1)],
+                vec![0],
+                vec![1],
+            ),
+            Automaton::new(
+                AutomatonType::NonDet,
+                4,
+                2,
+                vec![(0, 0, 1), (0, 1, 2), (1, 1, 3), (2, 2, 3), (3, 0, 3)],
+                vec![0],
+                vec![3],
+            ),
+            get_buffer_and_stack_aut(2, 2),
+        ];
+        for aut in &fixtures {
+            KINDS
+                .iter()
+                .for_each(|k| assert!(aut.determinized_checked(*k).is_ok()));
+        }
+
+        let incomplete_d =
+            Automaton::new(AutomatonType::Det, 2, 2, vec![(0, 1, 1)], vec![0], vec![1]);
+        assert_eq!(
+            incomplete_d.determinized_checked(AlgorithmKind::Sequential),
+            Err("State 0 has no transition on letter 2!".to_string())
+        );
+    }
+
     #[test]
     // Test the behaviour of determinization over a single state, no transition NFA.
     fn test_determinization_empty_lang() {
@@ -106,6 +199,37 @@ mod tests {
         });
     }
 
+    #[test]
+    // Test whether minimization reports the unreachable states it drops.
+    fn test_minimized_with_dropped() {
+        let unreachable_d = Automaton::new(
+            AutomatonType::Det,
+            3,
+            2,
+            vec![
+                (0, 1, 0),
+                (0, 2, 0),
+                (1, 1, 1),
+                (1, 2, 0),
+                (2, 1, 1),
+                (2, 2, 2),
+            ],
+            vec![0],
+            vec![0],
+        );
+        assert_eq!(unreachable_d.minimized().size, 3);
+        let (minimal, dropped) = unreachable_d.minimized_with_dropped();
+        assert_eq!(dropped, vec![1, 2]);
+        assert_eq!(minimal.size, 1);
+        assert_eq!(minimal.table, vec![(0, 1, 0), (0, 2, 0)]);
+
+        // Plain minimization keeps both unreachable states.
+        let (_, kept_dropped) = unreachable_d.minimized().minimized_with_dropped();
+        assert_eq!(kept_dropped.len(), 2);
+        let (_, none_dropped) = Automaton::accept_all(1, 2).minimized_with_dropped();
+        assert!(none_dropped.is_empty());
+    }
+
     #[test]
     // Test whether determinization can successfully produce a sinkhole state from an empty set of states.
     fn test_determinization_sinkhole() {
@@ -196,6 +320,59 @@ mod tests {
         });
     }
 
+    #[test]
+    // Test whether the determinization trace records the subsets explored for a set of states.
+    fn test_determinize_trace_set_of_states() {
+        let set_of_states_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            1,
+            vec![(0, 1, 0), (0, 1, 1)],
+            vec![0],
+            vec![1],
+        );
+        assert_eq!(
+            set_of_states_nd.determinize_trace(),
+            vec![
+                DeterminizeStep {
+                    source: 0,
+                    source_set: vec![0],
+                    letter: 1,
+                    target: 1,
+                    target_set: vec![0, 1],
+                    discovered: true,
+                },
+                DeterminizeStep {
+                    source: 1,
+                    source_set: vec![0, 1],
+                    letter: 1,
+                    target: 1,
+                    target_set: vec![0, 1],
+                    discovered: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    // Test whether a DFA state keeps the highest priority of the NFA accept states it contains.
+    fn test_determinization_priority() {
+        let mut priority_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            1,
+            vec![(0, 1, 1), (0, 1, 2), (1, 1, 3)],
+            vec![0],
+            vec![1, 2, 3],
+        );
+        priority_nd.accept_priority = Some(vec![5, 2, 7]);
+        let priority_d = priority_nd.determinized_with_priority();
+        assert_eq!(priority_d.size, 4);
+        assert_eq!(priority_d.end, vec![1, 2]);
+        assert_eq!(priority_d.accept_priority, Some(vec![2, 7]));
+        assert!(priority_d.equivalent(&priority_nd));
+    }
+
     #[test]
     // Test whether determinization identifies and deals with empty char transitions.
     fn test_determinization_empty_char() {
@@ -240,6 +417,140 @@ mod tests {
         });
     }
 
+    #[test]
+    // Test whether a complete DFA survives a round trip through its transition matrix.
+    fn test_transition_matrix_round_trip() {
+        let sinkhole_d = Automaton::new(
+            AutomatonType::Det,
+            4,
+            2,
+            vec![
+                (0, 1, 1),
+                (0, 2, 2),
+                (1, 1, 3),
+                (1, 2, 2),
+                (2, 1, 2),
+                (2, 2, 2),
+                (3, 1, 2),
+                (3, 2, 2),
+            ],
+            vec![0],
+            vec![3],
+        );
+        let matrix = sinkhole_d.transition_matrix().unwrap();
+        assert_eq!(matrix, vec![vec![1, 2], vec![3, 2], vec![2, 2], vec![2, 2]]);
+        let round_trip = Automaton::from_matrix(&matrix, 2, 0, vec![3]).order_transitions();
+        assert_eq!(round_trip.table, sinkhole_d.table);
+        assert_eq!(round_trip.start, sinkhole_d.start);
+        assert_eq!(round_trip.end, sinkhole_d.end);
+    }
+
+    #[test]
+    // Test whether incomplete or non deterministic automata have no transition matrix.
+    fn test_transition_matrix_incomplete() {
+        let incomplete_d =
+            Automaton::new(AutomatonType::Det, 2, 1, vec![(0, 1, 1)], vec![0], vec![1]);
+        assert_eq!(incomplete_d.transition_matrix(), None);
+        let set_of_states_nd = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            1,
+            vec![(0, 1, 0), (0, 1, 1)],
+            vec![0],
+            vec![1],
+        );
+        assert_eq!(set_of_states_nd.transition_matrix(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    // Test whether a matrix with a target out of range is rejected.
+    fn test_from_matrix_out_of_range() {
+        Automaton::from_matrix(&[vec![0, 2], vec![1, 1]], 2, 0, vec![1]);
+    }
+
+    #[test]
+    // Test whether multithreaded determinization agrees with the sequential one when the alphabet
+    // spans several letter batches.
+    fn test_determinization_wide_alphabet() {
+        let (size, alphabet) = (6, 40);
+        let wide_nd = Automaton::new(
+            AutomatonType::NonDet,
+            size,
+            alphabet,
+            (0..size)
+                .flat_map(|s| {
+                    (1..alphabet + 1)
+                        .flat_map(move |a| vec![(s, a, (s * a + 1) % size), (s, a, (s + a) % size)])
+                })
+                .collect(),
+            vec![0],
+            vec![size - 1],
+        );
+        let sequential = wide_nd.determinized(AlgorithmKind::Sequential);
+        let multithreaded = wide_nd.determinized(AlgorithmKind::Multithreaded(NUM_THREADS));
+        assert_eq!(sequential.size, multithreaded.size);
+        assert_eq!(sequential.table.len(), multithreaded.table.len());
+        assert!(sequential.equivalent(&multithreaded));
+    }
+
+    #[test]
+    // Test whether the multithreaded determinization numbers its states densely, and gives the
+    // same DFA as the sequential one up to renumbering.
+    fn test_determinization_mt_dense_ids() {
+        let bns = get_buffer_and_stack_aut(2, 3);
+        let sequential = bns.determinized(AlgorithmKind::Sequential).canonicalize();
+        for _ in 0..3 {
+            let multithreaded = bns.determinized(AlgorithmKind::Multithreaded(NUM_THREADS));
+            assert!(multithreaded
+                .table
+                .iter()
+                .all(|(s, _, e)| *s < multithreaded.size && *e < multithreaded.size));
+            let multithreaded = multithreaded.canonicalize();
+            assert_eq!(multithreaded.size, sequential.size);
+            assert_eq!(multithreaded.table, sequential.table);
+            assert_eq!(multithreaded.start, sequential.start);
+            assert_eq!(multithreaded.end, sequential.end);
+        }
+    }
+
+    #[test]
+    // Test whether many more workers than subsets, most of them stealing from the others, still
+    // give the sequential DFA up to renumbering.
+    fn test_determinization_mt_stealing() {
+        let bns = get_buffer_and_stack_aut(2, 2);
+        let sequential = bns.determinized(AlgorithmKind::Sequential).canonicalize();
+        for n_threads in [1, 2, 16, 64] {
+            let multithreaded = bns
+                .determinized(AlgorithmKind::Multithreaded(n_threads))
+                .canonicalize();
+            assert_eq!(multithreaded.size, sequential.size);
+            assert_eq!(multithreaded.table, sequential.table);
+            assert_eq!(multithreaded.end, sequential.end);
+        }
+    }
+
+    #[test]
+    // Test whether the rayon determinization numbers its states densely, and gives the same DFA
+    // as the sequential one up to renumbering.
+    fn test_determinization_rayon() {
+        for aut in [
+            get_buffer_and_stack_aut(2, 3),
+            get_random_nfa(12, 2, 36, 42),
+        ] {
+            let sequential = aut.determinized(AlgorithmKind::Sequential).canonicalize();
+            let rayon = aut.determinized(AlgorithmKind::Rayon);
+            assert!(rayon
+                .table
+                .iter()
+                .all(|(s, _, e)| *s < rayon.size && *e < rayon.size));
+            let rayon = rayon.canonicalize();
+            assert_eq!(rayon.size, sequential.size);
+            assert_eq!(rayon.table, sequential.table);
+            assert_eq!(rayon.end, sequential.end);
+        }
+    }
+
     #[test]
     // Test whether a machine minimizable into 2 partitions will be minimized as such.
     fn test_minimization_bipartite() {
@@ -266,10 +577,9 @@ mod tests {
             vec![0],
             vec![1],
         );
-        assert_eq!(
-            bipartite_big.minimized().order_transitions(),
-            bipartite_small
-        );
+        let bipartite_min = bipartite_big.minimized();
+        assert_eq!(bipartite_min, bipartite_small);
+        assert_eq!(bipartite_min.table, bipartite_small.table);
     }
 
     #[test]
@@ -303,17 +613,395 @@ mod tests {
             2,
             vec![
                 (0, 1, 0),
-                (0, 2, 2),
+                (0, 2, 1),
                 (1, 1, 1),
-                (1, 2, 1),
+                (1, 2, 2),
                 (2, 1, 2),
-                (2, 2, 1),
+                (2, 2, 2),
             ],
             vec![0],
+            vec![1],
+        );
+
+        let sep_min = sep_big.minimized();
+        KINDS
+            .iter()
+            .for_each(|k| assert_eq!(sep_big.minimal_state_count(*k), sep_min.size));
+        assert_eq!(sep_min, sep_small);
+        assert_eq!(sep_min.table, sep_small.table);
+        assert_eq!(sep_min.end, sep_small.end);
+
+        // Each split queues at most 2 splitters, and there are less splits than states.
+        let trace = sep_big.letter_partition_refinement_trace();
+        assert!(!trace.is_empty() && trace.len() <= 2 * sep_big.size);
+        let mut classes = trace.last().unwrap().clone();
+        classes.sort();
+        assert_eq!(classes, vec![vec![0, 3], vec![1, 2, 4], vec![5]]);
+        assert!(trace.windows(2).all(|w| w[0].len() <= w[1].len()));
+    }
+
+    #[test]
+    // Test whether two minimizations of the same automaton give identical tables.
+    fn test_minimization_deterministic_order() {
+        let buffer_and_stack =
+            get_buffer_and_stack_aut(2, 2).determinized(AlgorithmKind::Sequential);
+        let first = buffer_and_stack.minimized();
+        let second = buffer_and_stack.minimized();
+        assert_eq!(first.table, second.table);
+        assert_eq!(first.start, second.start);
+        assert_eq!(first.end, second.end);
+        assert!(first.table.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    // Test whether empty char transitions kept apart from the table determinize like letter 0
+    // transitions of the table.
+    fn test_determinize_epsilon_table() {
+        let letters = vec![(0, 1, 1), (1, 1, 3), (2, 2, 3), (3, 1, 3), (3, 2, 3)];
+        let epsilons = vec![(0, 2), (3, 0)];
+        let in_table = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            2,
+            letters
+                .iter()
+                .copied()
+                .chain(epsilons.iter().map(|(s, e)| (*s, 0, *e)))
+                .collect(),
+            vec![0],
+            vec![3],
+        );
+        let mut apart = Automaton::new(AutomatonType::NonDet, 4, 2, letters, vec![0], vec![3]);
+        apart.epsilon_table = Some(epsilons);
+        assert!(apart.has_epsilon());
+
+        for kind in KINDS {
+            let expected = in_table.determinized(kind);
+            let dfa = apart.determinized(kind);
+            assert!(dfa.equivalent(&expected));
+            if kind == AlgorithmKind::Sequential {
+                assert_eq!(dfa.size, expected.size);
+                assert_eq!(dfa.table, expected.table);
+                assert_eq!(dfa.end, expected.end);
+            }
+        }
+    }
+
+    #[test]
+    // Test whether the statistics of a determinization are bounded by the number of DFA states.
+    fn test_determinized_with_stats() {
+        let aut = get_buffer_and_stack_aut(2, 2);
+        let (dfa, stats) = aut.determinized_with_stats();
+        assert_eq!(dfa.table, aut.determinized(AlgorithmKind::Sequential).table);
+        assert_eq!(stats.subsets, dfa.size);
+        assert!(stats.peak_frontier >= 1 && stats.peak_frontier <= dfa.size);
+        assert!(stats.subset_bytes > 0);
+
+        let (_, det_stats) = dfa.determinized_with_stats();
+        assert_eq!(det_stats, DeterminizeStats::default());
+    }
+
+    #[test]
+    // Test whether small automata are determinized sequentially and large ones multithreaded.
+    fn test_determinized_auto() {
+        let sinkhole_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 1, 2)],
+            vec![0],
             vec![2],
         );
+        assert_eq!(sinkhole_nd.powerset_size_estimate(64), 4);
+        assert_eq!(sinkhole_nd.auto_algorithm_kind(), AlgorithmKind::Sequential);
+        assert_eq!(
+            sinkhole_nd.determinized_auto().table,
+            sinkhole_nd.determinized(AlgorithmKind::Sequential).table
+        );
+
+        let large_bns = get_buffer_and_stack_aut(4, 6);
+        assert_eq!(large_bns.powerset_size_estimate(64), 64);
+        if std::thread::available_parallelism().unwrap().get() > 1 {
+            assert!(matches!(
+                large_bns.auto_algorithm_kind(),
+                AlgorithmKind::Multithreaded(_)
+            ));
+        }
+    }
 
-        assert_eq!(sep_big.minimized().order_transitions(), sep_small);
+    /// In-memory frontier setting the cancel flag after a number of subsets are explored.
+    struct CancelAfter<'a> {
+        queue: VecDeque<Ubig>,
+        pops: usize,
+        cancel: &'a AtomicBool,
+    }
+
+    impl Frontier for CancelAfter<'_> {
+        fn push_back(&mut self, set: Ubig) -> io::Result<()> {
+            self.queue.push_back(set);
+            Ok(())
+        }
+
+        fn pop_front(&mut self) -> io::Result<Option<Ubig>> {
+            match self.pops {
+                0 => self.cancel.store(true, Ordering::Relaxed),
+                _ => self.pops -= 1,
+            }
+            Ok(self.queue.pop_front())
+        }
+    }
+
+    #[test]
+    // Test whether cancelling determinization midway gives a partial DFA whose explored states
+    // have all their transitions, matching the full determinization.
+    fn test_determinized_resumable() {
+        let bns = get_buffer_and_stack_aut(2, 3);
+        let full = bns.determinized(AlgorithmKind::Sequential);
+        let cancel = AtomicBool::new(false);
+        let frontier = CancelAfter {
+            queue: VecDeque::new(),
+            pops: 5,
+            cancel: &cancel,
+        };
+        let (partial, frontier) = match bns.determinized_resumable_with(frontier, &cancel) {
+            ResumableDeterminization::Cancelled { partial, frontier } => (partial, frontier),
+            ResumableDeterminization::Complete(_) => panic!("Determinization was not cancelled!"),
+        };
+        assert!(partial.size < full.size);
+        assert!(!frontier.is_empty());
+        for (s, a, e) in &partial.table {
+            assert!(*s < partial.size - frontier.len() && *e < partial.size);
+            assert!(*a >= 1 && *a <= partial.alphabet);
+        }
+        assert_eq!(
+            partial.table.len(),
+            (partial.size - frontier.len()) * bns.alphabet
+        );
+        assert!(partial.table.iter().all(|t| full.table.contains(t)));
+        assert!(partial.end.iter().all(|s| full.end.contains(s)));
+
+        let trace = bns.determinize_trace();
+        for (s, set) in &frontier {
+            assert!(trace
+                .iter()
+                .any(|step| step.target == *s && step.target_set == *set));
+        }
+
+        cancel.store(false, Ordering::Relaxed);
+        match bns.determinized_resumable(&cancel) {
+            ResumableDeterminization::Complete(dfa) => assert_eq!(dfa.table, full.table),
+            ResumableDeterminization::Cancelled { .. } => panic!("Determinization was cancelled!"),
+        }
+    }
+
+    #[test]
+    // Test whether a DFA missing its dead transitions is completed before minimization, and a
+    // complete DFA gets no sink.
+    fn test_minimization_incomplete() {
+        let ab_partial_d = Automaton::new(
+            AutomatonType::Det,
+            4,
+            2,
+            vec![(0, 1, 1), (1, 2, 2), (3, 1, 1), (3, 2, 3)],
+            vec![0],
+            vec![2],
+        );
+        let min = ab_partial_d.minimized();
+        assert_eq!(min.find_missing_transition(), None);
+        assert_eq!(min.size, 5);
+        assert!(min.equivalent(&ab_partial_d));
+        assert!(min.accepts(&[1, 2]));
+        assert!(!min.accepts(&[1]));
+        assert_eq!(min.table, ab_partial_d.completed().minimized().table);
+        assert_eq!(ab_partial_d.clone().into_minimized().table, min.table);
+
+        let complete = min.minimized();
+        assert_eq!(complete.size, min.size);
+        assert_eq!(complete.table, min.table);
+
+        let a_partial_d =
+            Automaton::new(AutomatonType::Det, 2, 2, vec![(0, 1, 1)], vec![0], vec![1]);
+        assert_eq!(a_partial_d.minimized().size, 3);
+        KINDS
+            .iter()
+            .for_each(|k| assert_eq!(a_partial_d.minimal_state_count(*k), 3));
+    }
+
+    #[test]
+    // Test whether the fused determinization and minimization matches determinizing then
+    // minimizing, also when merging after every explored state.
+    fn test_determinize_minimize_fused() {
+        let sinkhole_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 1, 2)],
+            vec![0],
+            vec![2],
+        );
+        let mut fixtures = vec![sinkhole_nd, get_buffer_and_stack_aut(2, 3)];
+        fixtures.extend((0..8).map(|seed| get_random_nfa(6, 2, 12, seed)));
+        for aut in fixtures {
+            let expected = aut.determinized(AlgorithmKind::Sequential).minimized();
+            let fused = aut.determinize_minimize_fused();
+            assert_eq!(fused.size, expected.size);
+            assert!(fused.equivalent(&expected));
+
+            let (table, size, start, end) = rabin_scott_fused(&aut, 1);
+            let merged = Automaton::new(AutomatonType::Det, size, aut.alphabet, table, start, end);
+            assert!(merged.size <= aut.determinized(AlgorithmKind::Sequential).size);
+            assert!(merged.equivalent(&expected));
+        }
+    }
+
+    #[test]
+    // Test whether the sorted transitions match the ones of an ordered automaton.
+    fn test_transition_triples_sorted() {
+        let mut aut = get_buffer_and_stack_aut(2, 2);
+        aut.table.reverse();
+        aut.end.reverse();
+        let (table, start, end) = aut.to_transition_triples_sorted();
+        assert_ne!(table, aut.table);
+        let ordered = aut.order_transitions();
+        assert_eq!(table, ordered.table);
+        assert_eq!(start, ordered.start);
+        assert_eq!(end, ordered.end);
+    }
+
+    /// Check that the minimized determinization of an automaton is deterministic, complete and
+    /// left unchanged by determinizing and minimizing it again.
+    fn is_stable_minimal_dfa(aut: &Automaton, kind: AlgorithmKind) -> bool {
+        let dfa = aut.determinized(kind).minimized();
+        let again = dfa.determinized(kind).minimized();
+        dfa.is_deterministic()
+            && dfa.is_complete()
+            && dfa.minimal_state_count(kind) == dfa.size
+            && dfa.canonicalize().to_transition_triples_sorted()
+                == again.canonicalize().to_transition_triples_sorted()
+    }
+
+    /// Remove transitions and states from an automaton for as long as it keeps failing a check.
+    fn shrink_failure(mut aut: Automaton, check: impl Fn(&Automaton) -> bool) -> Automaton {
+        let mut shrunk = true;
+        while shrunk {
+            shrunk = false;
+            for i in 0..aut.table.len() + aut.start.len() + aut.end.len() {
+                let mut smaller = aut.clone();
+                let (n_table, n_start) = (aut.table.len(), aut.start.len());
+                if i < n_table {
+                    smaller.table.remove(i);
+                } else if i < n_table + n_start {
+                    smaller.start.remove(i - n_table);
+                } else {
+                    smaller.end.remove(i - n_table - n_start);
+                }
+                if !check(&smaller) {
+                    aut = smaller;
+                    shrunk = true;
+                    break;
+                }
+            }
+        }
+        aut
+    }
+
+    #[test]
+    // Test whether determinizing then minimizing random automata gives minimal DFAs that another
+    // determinization and minimization leaves unchanged.
+    fn test_determinize_minimize_idempotent() {
+        for seed in 0..40 {
+            let aut = get_random_nfa(2 + (seed as usize) % 6, 1 + (seed as usize) % 3, 16, seed);
+            for kind in KINDS {
+                let check = |a: &Automaton| is_stable_minimal_dfa(a, kind);
+                if !check(&aut) {
+                    panic!(
+                        "Seed {} fails with {:?}, shrunk to {:?}",
+                        seed,
+                        kind,
+                        shrink_failure(aut.clone(), check)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    // Test whether the minimized table does not depend on the order of the input transitions.
+    fn test_minimization_table_order_stable() {
+        let dfa = get_buffer_and_stack_aut(2, 3).determinized(AlgorithmKind::Sequential);
+        let mut reversed = dfa.clone();
+        reversed.table.reverse();
+        let first = dfa.minimized();
+        assert_eq!(first.table, dfa.minimized().table);
+        assert_eq!(first.table, reversed.minimized().table);
+    }
+
+    #[test]
+    // Test whether reversing and determinizing twice gives back the language of the automaton.
+    fn test_reverse_dfa_twice() {
+        let empty_char_nd = Automaton::new(
+            AutomatonType::NonDet,
+            4,
+            2,
+            vec![
+                (0, 0, 1),
+                (0, 1, 2),
+                (1, 1, 3),
+                (2, 2, 3),
+                (3, 0, 3),
+                (3, 1, 3),
+                (3, 2, 3),
+            ],
+            vec![0],
+            vec![3],
+        );
+        KINDS.iter().for_each(|k| {
+            let twice = empty_char_nd.reverse_dfa(*k).reverse_dfa(*k);
+            assert!(twice.equivalent(&empty_char_nd.determinized(*k)));
+            assert!(twice.equivalent(&empty_char_nd.brzozowski_minimized(*k)));
+        });
+        let twice = empty_char_nd
+            .reverse_dfa(AlgorithmKind::Sequential)
+            .reverse_dfa(AlgorithmKind::Sequential);
+        assert_eq!(
+            twice.size,
+            empty_char_nd.minimal_state_count(AlgorithmKind::Sequential)
+        );
+    }
+
+    #[test]
+    // Test whether toggling an accept state then reminimizing matches a minimization from scratch.
+    fn test_toggle_accept_and_remap() {
+        let unmin = Automaton::new(
+            AutomatonType::Det,
+            4,
+            2,
+            vec![
+                (0, 1, 1),
+                (0, 2, 2),
+                (1, 1, 2),
+                (1, 2, 3),
+                (2, 1, 2),
+                (2, 2, 2),
+                (3, 1, 1),
+                (3, 2, 3),
+            ],
+            vec![0],
+            vec![3],
+        );
+        let mut toggled = unmin.clone();
+        toggled.end.push(1);
+        let expected = toggled.minimized();
+
+        let mut remapped = unmin.clone();
+        remapped.toggle_accept_and_remap(1);
+        assert_eq!(remapped.table, expected.table);
+        assert_eq!(remapped.end, expected.end);
+        assert!(remapped.accepts(&[1]));
+
+        let accepts = remapped.end.len();
+        remapped.toggle_accept_and_remap(remapped.end[0]);
+        assert_eq!(remapped.end.len(), accepts - 1);
     }
 
     #[test]
@@ -336,8 +1024,152 @@ mod tests {
             vec![0],
             vec![3],
         )
-        .minimized()
-        .order_transitions();
+        .minimized();
         assert_eq!(unmin_small.size, 4);
     }
+
+    #[test]
+    // Test whether checked minimization accepts correct minimizations and rejects a wrong
+    // partition of the states.
+    fn test_minimized_checked() {
+        let sep_big = Automaton::new(
+            AutomatonType::Det,
+            6,
+            2,
+            vec![
+                (0, 1, 3),
+                (0, 2, 1),
+                (1, 1, 2),
+                (1, 2, 5),
+                (2, 1, 2),
+                (2, 2, 5),
+                (3, 1, 0),
+                (3, 2, 4),
+                (4, 1, 2),
+                (4, 2, 5),
+                (5, 1, 5),
+                (5, 2, 5),
+            ],
+            vec![0],
+            vec![1, 2, 4],
+        );
+        let fixtures = [
+            sep_big.clone(),
+            Automaton::accept_none(3, 2),
+            get_buffer_and_stack_aut(2, 2).determinized(AlgorithmKind::Sequential),
+        ];
+        for aut in &fixtures {
+            let min = aut.minimized_checked(8).unwrap();
+            assert_eq!(min.table, aut.minimized().table);
+        }
+
+        // Merging the accept states 1 and 2 with the sink 5 loses the word `2`.
+        let wrong_p: HashMap<usize, usize> =
+            HashMap::from([(0, 0), (1, 1), (2, 1), (3, 2), (4, 3), (5, 1)]);
+        let wrong_min = sep_big.quotient(&wrong_p, 4);
+        assert!(sep_big.check_same_words(&wrong_min, 8).is_err());
+        assert!(sep_big.check_same_words(&wrong_min, 0).is_ok());
+    }
+
+    #[test]
+    // Test whether the pipeline logs the state count of each stage in order.
+    fn test_pipeline_with_log() {
+        let sinkhole_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 1, 2)],
+            vec![0],
+            vec![2],
+        );
+        let mut stages: Vec<(String, usize)> = Vec::new();
+        let minimal = sinkhole_nd.pipeline_with_log(AlgorithmKind::Sequential, |stage, size| {
+            stages.push((stage.to_string(), size))
+        });
+        assert_eq!(
+            stages,
+            vec![
+                ("determinized".to_string(), 4),
+                ("minimized".to_string(), 4),
+                ("trimmed".to_string(), 3),
+            ]
+        );
+        assert_eq!(minimal.size, 4);
+
+        // The trimmed count matches removing the sink, including when the sink is the start.
+        let empty_language = Automaton::new(AutomatonType::NonDet, 2, 1, vec![], vec![0], vec![1]);
+        for automaton in [get_buffer_and_stack_aut(2, 2), empty_language] {
+            let mut trimmed = 0;
+            let minimal = automaton.pipeline_with_log(AlgorithmKind::Sequential, |stage, size| {
+                if stage == "trimmed" {
+                    trimmed = size;
+                }
+            });
+            assert_eq!(trimmed, minimal.without_sink().size);
+        }
+    }
+
+    #[test]
+    // Test whether the pipeline benchmark returns one nonzero timing per iteration.
+    fn test_benchmark_pipeline() {
+        let automaton = get_buffer_and_stack_aut(2, 2);
+        KINDS.iter().for_each(|k| {
+            let timings = automaton.benchmark_pipeline(*k, 3);
+            assert_eq!(timings.len(), 3);
+            timings.iter().for_each(|t| {
+                assert!(!t.determinize.is_zero());
+                assert!(!t.minimize.is_zero());
+                assert_eq!(t.to_csv_row().split(',').count(), 2);
+            });
+        });
+        assert_eq!(PipelineTiming::CSV_HEADER.split(',').count(), 2);
+    }
+
+    #[test]
+    // Test whether every determinization variant keeps the alphabet labels of the automaton.
+    fn test_determinization_alphabet_labels() {
+        let mut labelled_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (0, 1, 0), (1, 2, 2), (0, 0, 2)],
+            vec![0],
+            vec![2],
+        );
+        let labels = Some(vec!["x".to_string(), "y".to_string()]);
+        labelled_nd.alphabet_labels = labels.clone();
+        labelled_nd.accept_priority = Some(vec![1]);
+
+        let cancel = AtomicBool::new(false);
+        let resumed = match labelled_nd.determinized_resumable(&cancel) {
+            ResumableDeterminization::Complete(dfa) => dfa,
+            ResumableDeterminization::Cancelled { .. } => panic!("Nothing cancelled it!"),
+        };
+        let dfas = [
+            labelled_nd.determinized(AlgorithmKind::Sequential),
+            labelled_nd.determinized_spilling(1).unwrap(),
+            labelled_nd.determinized_trie(),
+            labelled_nd.determinized_with_stats().0,
+            labelled_nd.determinize_minimize_fused(),
+            labelled_nd.determinized_with_priority(),
+            resumed,
+        ];
+        for dfa in dfas {
+            assert_eq!(dfa.alphabet_labels, labels);
+            assert_eq!(dfa.minimized().alphabet_labels, labels);
+        }
+    }
+
+    #[test]
+    // Test whether the determinization trace numbers subsets as the sequential determinization.
+    fn test_determinize_trace_numbering() {
+        for aut in [get_buffer_and_stack_aut(2, 3), get_random_nfa(12, 3, 30, 7)] {
+            let trace: Vec<(usize, usize, usize)> = aut
+                .determinize_trace()
+                .iter()
+                .map(|step| (step.source, step.letter, step.target))
+                .collect();
+            assert_eq!(trace, aut.determinized(AlgorithmKind::Sequential).table);
+        }
+    }
 }
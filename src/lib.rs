@@ -1,7 +1,33 @@
 #![feature(scoped_threads)]
 pub mod automaton;
+mod automaton_analysis;
+pub mod automaton_compact;
 pub mod automaton_encoder;
+pub mod automaton_matcher;
 mod automaton_multithreaded;
+pub mod automaton_multitrack;
+mod automaton_operations;
+pub mod automaton_pool;
+mod automaton_rayon;
 mod automaton_sequential;
+pub mod automaton_session;
+pub mod regex;
+mod rng;
+mod spill_queue;
+pub mod state_set;
 pub mod transition_graphs;
+pub mod trie;
 mod ubig;
+
+mod automaton_analysis_test;
+mod automaton_compact_test;
+mod automaton_encoder_test;
+mod automaton_matcher_test;
+mod automaton_multitrack_test;
+mod automaton_operations_test;
+mod automaton_pool_test;
+mod automaton_session_test;
+mod automaton_test;
+mod regex_test;
+mod state_set_test;
+mod trie_test;
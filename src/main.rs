@@ -1,23 +1,14 @@
-#![feature(scoped_threads)]
-mod automaton;
-pub mod automaton_encoder;
-mod automaton_multithreaded;
-mod automaton_sequential;
-mod automaton_test;
-mod transition_graphs;
-mod ubig;
-
 use std::{
     fmt::Debug,
     fs::{self, File},
-    io::Write,
+    io::{self, BufWriter, Write},
     path::PathBuf,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
-use automaton::{AlgorithmKind, Automaton};
 use clap::{Parser, ValueEnum};
-use transition_graphs::{get_buffer_and_stack_aut, get_two_stack_aut};
+use nfdeterminize::automaton::{AlgorithmKind, Automaton};
+use nfdeterminize::transition_graphs::{get_buffer_and_stack_aut, get_two_stack_aut};
 
 static N_THREADS: usize = 12;
 
@@ -38,13 +29,21 @@ struct ProgramArguments {
     #[clap(short, long)]
     n_threads: Option<usize>,
 
-    /// Specify whether the system should be run sequentially, multithreaded, or multiprocessed
+    /// Specify whether the system should be run sequentially, multithreaded, or on the rayon thread pool
     #[clap(short, long, value_enum)]
     mode: Option<AlgorithmAction>,
 
-    #[clap(short, long)]
+    #[clap(short, long, global = true)]
     /// File to print the automaton to
     file: Option<PathBuf>,
+
+    /// Comma separated start states overriding the start states of the automaton
+    #[clap(long, value_delimiter = ',')]
+    start: Option<Vec<usize>>,
+
+    /// Format to print the final automaton in
+    #[clap(long = "format", value_enum)]
+    output_format: Option<OutputFormat>,
 }
 
 impl ProgramArguments {
@@ -58,13 +57,23 @@ impl ProgramArguments {
         let format: &AutomatonFormat = match &self.action {
             Action::Run { format, .. } => format,
             Action::Minimize { format } => format,
-            Action::Determinize { format } => format,
+            Action::Determinize { format, .. } => format,
         };
         match format {
+            AutomatonFormat::File { fp } if fp.extension().is_some_and(|e| e == "lz4") => {
+                self.print_verbose("Parsing automaton from compressed file...");
+                Automaton::from_compressed_file(fp).unwrap_or_else(|e| {
+                    eprintln!("Could not read {}: {}", fp.to_str().unwrap(), e);
+                    std::process::exit(1);
+                })
+            }
             AutomatonFormat::File { fp } => match fs::read_to_string(&fp) {
                 Ok(aut) => {
                     self.print_verbose("Parsing automaton from file...");
-                    Automaton::from(&aut.to_string())
+                    Automaton::try_from(aut.as_str()).unwrap_or_else(|e| {
+                        eprintln!("Could not parse {}: {}", fp.to_str().unwrap(), e);
+                        std::process::exit(1);
+                    })
                 }
                 Err(_) => {
                     eprintln!(
@@ -90,6 +99,14 @@ impl ProgramArguments {
 enum AlgorithmAction {
     Sequential,
     Multithreaded,
+    Rayon,
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+enum OutputFormat {
+    Gap,
+    Csv,
+    Dot,
 }
 
 #[derive(Debug, Copy, Clone, ValueEnum)]
@@ -107,6 +124,11 @@ enum Action {
 
         #[clap(short, long, value_enum)]
         method: Option<MinimizationMethod>,
+
+        /// Write the minimized automaton as soon as it is built, without keeping the
+        /// determinized automaton in memory
+        #[clap(long)]
+        stream: bool,
     },
     /// Run minimization.
     Minimize {
@@ -118,6 +140,10 @@ enum Action {
     Determinize {
         #[clap(subcommand)]
         format: AutomatonFormat,
+
+        /// Run both sequential and multithreaded determinization and compare the results
+        #[clap(long)]
+        compare: bool,
     },
 }
 
@@ -131,13 +157,114 @@ enum AutomatonFormat {
     TwoStack { n1: usize, n2: usize },
 }
 
+/// Results of running both determinization strategies over the same automaton.
+struct DeterminizationComparison {
+    dfa: Automaton,
+    sequential_time: Duration,
+    multithreaded_time: Duration,
+}
+
+/// Determinize an automaton both sequentially and multithreaded, and check that both results
+/// recognize the same language. Returns an error if the two strategies disagree.
+fn compare_determinizations(
+    automaton: &Automaton,
+    n_threads: usize,
+) -> Result<DeterminizationComparison, String> {
+    compare_determinizations_with(
+        automaton,
+        |aut| aut.determinized(AlgorithmKind::Sequential),
+        |aut| aut.determinized(AlgorithmKind::Multithreaded(n_threads)),
+    )
+}
+
+/// Determinize an automaton with the given sequential and multithreaded determinizations, and
+/// check that both results recognize the same language.
+fn compare_determinizations_with(
+    automaton: &Automaton,
+    sequential: impl Fn(&Automaton) -> Automaton,
+    multithreaded: impl Fn(&Automaton) -> Automaton,
+) -> Result<DeterminizationComparison, String> {
+    let start = Instant::now();
+    let sequential = sequential(automaton);
+    let sequential_time = start.elapsed();
+
+    let start = Instant::now();
+    let multithreaded = multithreaded(automaton);
+    let multithreaded_time = start.elapsed();
+
+    if !sequential.equivalent(&multithreaded) {
+        return Err(format!(
+            "Sequential ({} states) and multithreaded ({} states) determinization disagree!",
+            sequential.size, multithreaded.size
+        ));
+    }
+    Ok(DeterminizationComparison {
+        dfa: sequential,
+        sequential_time,
+        multithreaded_time,
+    })
+}
+
+/// Print the timings of a comparison of determinization strategies and get its DFA, or print
+/// why the strategies disagree and get the exit code of the program.
+fn report_comparison(
+    comparison: Result<DeterminizationComparison, String>,
+    n_threads: usize,
+) -> Result<Automaton, i32> {
+    match comparison {
+        Ok(comparison) => {
+            println!(
+                "Sequential: {:?} seconds. Multithreaded ({} threads): {:?} seconds.",
+                comparison.sequential_time.as_secs_f64(),
+                n_threads,
+                comparison.multithreaded_time.as_secs_f64()
+            );
+            Ok(comparison.dfa)
+        }
+        Err(msg) => {
+            eprintln!("{}", msg);
+            Err(1)
+        }
+    }
+}
+
+/// Determinize then minimize an automaton, writing the result to a file or to stdout without
+/// keeping the determinized automaton in memory along with the result.
+fn stream_pipeline(
+    automaton: &Automaton,
+    mode: AlgorithmKind,
+    file: Option<&PathBuf>,
+) -> io::Result<()> {
+    match file {
+        Some(fp) => automaton.pipeline_to_writer(mode, &mut BufWriter::new(File::create(fp)?)),
+        None => automaton.pipeline_to_writer(mode, &mut io::stdout().lock()),
+    }
+}
+
+/// Write the final automaton in the chosen output format, the automaton file format read back by
+/// the program being the default. Returns an error if the automaton cannot be written in the
+/// automaton file format.
+fn format_automaton(automaton: &Automaton, format: Option<OutputFormat>) -> Result<String, String> {
+    match format {
+        None | Some(OutputFormat::Gap) => automaton.to_nfa_string(),
+        Some(OutputFormat::Csv) => Ok(automaton.to_csv()),
+        Some(OutputFormat::Dot) => Ok(automaton.to_dot()),
+    }
+}
+
 /// Main function of the program. Takes arguments:
 /// + Only 1 argument is allowed - the finite state machine file.
 /// + If there are more/less arguments than 1, the program will fail.
 fn main() {
     let clap_args = ProgramArguments::parse();
 
-    let automaton = clap_args.get_automaton();
+    let mut automaton = clap_args.get_automaton();
+    if let Some(start) = &clap_args.start {
+        automaton = automaton.with_start(start).unwrap_or_else(|msg| {
+            eprintln!("{}", msg);
+            std::process::exit(1);
+        });
+    }
     if clap_args.verbose {
         println!("Automaton size: {:?}", automaton.size);
     }
@@ -154,6 +281,7 @@ fn main() {
     let mode = match mode {
         AlgorithmAction::Sequential => AlgorithmKind::Sequential,
         AlgorithmAction::Multithreaded => AlgorithmKind::Multithreaded(n_threads),
+        AlgorithmAction::Rayon => AlgorithmKind::Rayon,
     };
 
     let start = SystemTime::now()
@@ -161,6 +289,15 @@ fn main() {
         .expect("")
         .as_millis();
 
+    if let Action::Run { stream: true, .. } = clap_args.action {
+        clap_args.print_verbose("Determinizing and minimizing automata to output...\n");
+        if let Err(e) = stream_pipeline(&automaton, mode, clap_args.file.as_ref()) {
+            eprintln!("Writing the automaton failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let final_dfa = match clap_args.action {
         Action::Run { method, .. } => {
             let method = match method {
@@ -169,22 +306,19 @@ fn main() {
             };
             match method {
                 MinimizationMethod::PartitionRefine => {
-                    clap_args.print_verbose("Determinizing automata... ");
-                    let new_dfa = automaton.determinized(mode);
-                    if clap_args.verbose {
-                        println!("Intermediate Automaton Size: {:?}", new_dfa.size);
-                    }
-                    clap_args.print_verbose("Minimizing automata... ");
-                    new_dfa.minimized()
+                    clap_args.print_verbose("Determinizing and minimizing automata...\n");
+                    automaton.pipeline_with_log(mode, |stage, size| {
+                        clap_args.print_verbose(&format!("Automaton size {}: {}\n", stage, size))
+                    })
                 }
                 MinimizationMethod::Brzozowski => {
                     clap_args.print_verbose("Determinizing automata... ");
-                    let new_dfa = automaton.reverse_transitions().determinized(mode);
+                    let new_dfa = automaton.reverse_dfa(mode);
                     if clap_args.verbose {
                         println!("Intermediate Automaton Size: {:?}", new_dfa.size);
                     }
                     clap_args.print_verbose("Redeterminizing automata... ");
-                    new_dfa.reverse_transitions().determinized(mode)
+                    new_dfa.reverse_dfa(mode)
                 }
             }
         }
@@ -192,6 +326,11 @@ fn main() {
             clap_args.print_verbose("Minimizing automata... ");
             automaton.minimized()
         }
+        Action::Determinize { compare: true, .. } => {
+            clap_args.print_verbose("Comparing determinization strategies... ");
+            report_comparison(compare_determinizations(&automaton, n_threads), n_threads)
+                .unwrap_or_else(|code| std::process::exit(code))
+        }
         Action::Determinize { .. } => {
             clap_args.print_verbose("Determinizing automata... ");
             automaton.determinized(mode)
@@ -200,16 +339,20 @@ fn main() {
 
     // Print final dfa to file/stdout
     clap_args.print_verbose(&format!("Final Automaton size: {:?}\n", final_dfa.size));
+    let output = format_automaton(&final_dfa, clap_args.output_format).unwrap_or_else(|msg| {
+        eprintln!("{}", msg);
+        std::process::exit(1);
+    });
     if let Some(fp) = clap_args.file {
         if let Ok(mut f) = File::create(fp.clone()) {
-            if let Err(_) = f.write_all(format!("{final_dfa:?}").as_bytes()) {
+            if f.write_all(output.as_bytes()).is_err() {
                 eprintln!("Writing to file failed!");
             }
         } else {
             eprintln!("File {:?} already exists!", fp);
         }
     } else {
-        println!("{:?}", final_dfa);
+        println!("{}", output);
     }
 
     if clap_args.timed {
@@ -223,3 +366,93 @@ fn main() {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compare_determinizations, compare_determinizations_with, format_automaton,
+        report_comparison, stream_pipeline, OutputFormat, ProgramArguments,
+    };
+    use clap::Parser;
+    use nfdeterminize::automaton::{AlgorithmKind, Automaton, AutomatonType};
+    use nfdeterminize::transition_graphs::get_buffer_and_stack_aut;
+    use std::fs;
+
+    #[test]
+    // Test whether both determinization strategies agree on a generated automaton.
+    fn test_compare_determinizations() {
+        let automaton = get_buffer_and_stack_aut(2, 2);
+        let comparison = compare_determinizations(&automaton, 4).unwrap();
+        assert!(comparison.dfa.equivalent(&automaton));
+    }
+
+    #[test]
+    // Test whether determinizations recognizing different languages are reported as a mismatch
+    // that makes the program exit with an error.
+    fn test_compare_determinizations_mismatch() {
+        let automaton = get_buffer_and_stack_aut(2, 2);
+        let comparison = compare_determinizations_with(
+            &automaton,
+            |aut| aut.determinized(AlgorithmKind::Sequential),
+            |aut| aut.determinized(AlgorithmKind::Sequential).complement(),
+        );
+        let msg = comparison.as_ref().err().unwrap();
+        assert!(msg.contains("determinization disagree!"));
+        assert_ne!(report_comparison(comparison, 4).unwrap_err(), 0);
+    }
+
+    #[test]
+    // Test whether the streamed pipeline writes the same automaton as the in-memory pipeline.
+    fn test_stream_pipeline() {
+        let automaton = get_buffer_and_stack_aut(2, 2);
+        let path = std::env::temp_dir().join(format!(
+            "nfdeterminize-stream-test-{}.nfa",
+            std::process::id()
+        ));
+        stream_pipeline(&automaton, AlgorithmKind::Sequential, Some(&path)).unwrap();
+        let streamed = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let expected = automaton.pipeline_with_log(AlgorithmKind::Sequential, |_, _| {});
+        assert_eq!(streamed, format!("{}\n", expected));
+        let mut parsed = Automaton::from(&streamed);
+        parsed.table.sort();
+        assert_eq!(parsed.table, expected.table);
+    }
+
+    #[test]
+    // Test whether the default output of run writes the same automaton file as run --stream.
+    fn test_default_output_matches_stream() {
+        let clap_args = ProgramArguments::parse_from(["nfdeterminize", "run", "bns", "2", "2"]);
+        let automaton = clap_args.get_automaton();
+        let path = std::env::temp_dir().join(format!(
+            "nfdeterminize-default-output-test-{}.nfa",
+            std::process::id()
+        ));
+        stream_pipeline(&automaton, AlgorithmKind::Sequential, Some(&path)).unwrap();
+        let streamed = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let final_dfa = automaton.pipeline_with_log(AlgorithmKind::Sequential, |_, _| {});
+        let output = format_automaton(&final_dfa, clap_args.output_format).unwrap();
+        assert!(output.starts_with("{\"det\""));
+        assert_eq!(format!("{}\n", output), streamed);
+    }
+
+    #[test]
+    // Test whether writing an automaton the file format cannot hold gives an error rather than
+    // panicking, while the other formats still write it.
+    fn test_format_automaton_error() {
+        let mut many_letters = Automaton::new(
+            AutomatonType::NonDet,
+            2,
+            53,
+            vec![(0, 53, 1)],
+            vec![0],
+            vec![1],
+        );
+        many_letters.epsilon_table = Some(vec![(1, 0)]);
+        assert!(format_automaton(&many_letters, None).is_err());
+        assert!(format_automaton(&many_letters, Some(OutputFormat::Csv)).is_ok());
+    }
+}
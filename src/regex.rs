@@ -0,0 +1,257 @@
+use std::{collections::BTreeSet, error::Error, fmt};
+
+use crate::automaton::{Automaton, AutomatonType};
+
+/// Characters with a meaning in a regular expression - They are matched literally when escaped
+/// with a backslash.
+const OPERATORS: &str = "|()*+?\\";
+
+/// Error found while parsing a regular expression, at a character of the pattern counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegexError {
+    /// A `)` closes no group.
+    UnexpectedChar { position: usize, found: char },
+    /// A `(` is never closed.
+    UnclosedGroup { position: usize },
+    /// A `*`, `+` or `?` follows no expression.
+    NothingToRepeat { position: usize, operator: char },
+    /// The pattern ends with a backslash escaping nothing.
+    DanglingEscape { position: usize },
+}
+
+impl fmt::Display for RegexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegexError::UnexpectedChar { position, found } => {
+                write!(f, "Unexpected '{}' at character {}!", found, position)
+            }
+            RegexError::UnclosedGroup { position } => {
+                write!(f, "Group opened at character {} is never closed!", position)
+            }
+            RegexError::NothingToRepeat { position, operator } => write!(
+                f,
+                "Operator '{}' at character {} repeats nothing!",
+                operator, position
+            ),
+            RegexError::DanglingEscape { position } => {
+                write!(f, "Backslash at character {} escapes nothing!", position)
+            }
+        }
+    }
+}
+
+impl Error for RegexError {}
+
+/// Syntax tree of a regular expression.
+#[derive(Debug)]
+enum Regex {
+    /// Matches the empty word only.
+    Empty,
+    Literal(char),
+    Concat(Vec<Regex>),
+    Alt(Vec<Regex>),
+    Star(Box<Regex>),
+    Plus(Box<Regex>),
+    Optional(Box<Regex>),
+}
+
+/// Recursive descent parser of regular expressions, over the characters of a pattern.
+struct RegexParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl RegexParser {
+    /// alt := concat ('|' concat)*
+    fn parse_alt(&mut self) -> Result<Regex, RegexError> {
+        let mut branches = vec![self.parse_concat()?];
+        while self.chars.get(self.pos) == Some(&'|') {
+            self.pos += 1;
+            branches.push(self.parse_concat()?);
+        }
+        Ok(match branches.len() {
+            1 => branches.pop().unwrap(),
+            _ => Regex::Alt(branches),
+        })
+    }
+
+    /// concat := repeat* - An empty concatenation matches the empty word.
+    fn parse_concat(&mut self) -> Result<Regex, RegexError> {
+        let mut factors = Vec::new();
+        while let Some(c) = self.chars.get(self.pos) {
+            if *c == '|' || *c == ')' {
+                break;
+            }
+            factors.push(self.parse_repeat()?);
+        }
+        Ok(match factors.len() {
+            0 => Regex::Empty,
+            1 => factors.pop().unwrap(),
+            _ => Regex::Concat(factors),
+        })
+    }
+
+    /// repeat := atom ('*' | '+' | '?')*
+    fn parse_repeat(&mut self) -> Result<Regex, RegexError> {
+        let mut regex = self.parse_atom()?;
+        while let Some(c) = self.chars.get(self.pos) {
+            regex = match c {
+                '*' => Regex::Star(Box::new(regex)),
+                '+' => Regex::Plus(Box::new(regex)),
+                '?' => Regex::Optional(Box::new(regex)),
+                _ => break,
+            };
+            self.pos += 1;
+        }
+        Ok(regex)
+    }
+
+    /// atom := '(' alt ')' | '\' char | literal
+    fn parse_atom(&mut self) -> Result<Regex, RegexError> {
+        let position = self.pos + 1;
+        let c = self.chars[self.pos];
+        self.pos += 1;
+        match c {
+            '(' => {
+                let regex = self.parse_alt()?;
+                match self.chars.get(self.pos) {
+                    Some(')') => {
+                        self.pos += 1;
+                        Ok(regex)
+                    }
+                    _ => Err(RegexError::UnclosedGroup { position }),
+                }
+            }
+            '\\' => match self.chars.get(self.pos) {
+                Some(escaped) => {
+                    self.pos += 1;
+                    Ok(Regex::Literal(*escaped))
+                }
+                None => Err(RegexError::DanglingEscape { position }),
+            },
+            '*' | '+' | '?' => Err(RegexError::NothingToRepeat {
+                position,
+                operator: c,
+            }),
+            c => Ok(Regex::Literal(c)),
+        }
+    }
+}
+
+/// Epsilon-NFA built from a syntax tree by Thompson's construction.
+struct ThompsonBuilder {
+    size: usize,
+    table: Vec<(usize, usize, usize)>,
+    /// Letters in increasing order of character, letter `i` being `letters[i - 1]`.
+    letters: Vec<char>,
+}
+
+impl ThompsonBuilder {
+    fn new_state(&mut self) -> usize {
+        self.size += 1;
+        self.size - 1
+    }
+
+    /// Build the fragment recognizing a regular expression, with a single start state and a
+    /// single accept state that has no outgoing transitions. Returns: (start, accept).
+    fn build(&mut self, regex: &Regex) -> (usize, usize) {
+        let start = self.new_state();
+        let accept = match regex {
+            Regex::Empty => {
+                let accept = self.new_state();
+                self.table.push((start, 0, accept));
+                accept
+            }
+            Regex::Literal(c) => {
+                let accept = self.new_state();
+                let letter = self.letters.binary_search(c).unwrap() + 1;
+                self.table.push((start, letter, accept));
+                accept
+            }
+            Regex::Concat(factors) => factors.iter().fold(start, |end, factor| {
+                let (s, e) = self.build(factor);
+                self.table.push((end, 0, s));
+                e
+            }),
+            Regex::Alt(branches) => {
+                let ends: Vec<usize> = branches
+                    .iter()
+                    .map(|branch| {
+                        let (s, e) = self.build(branch);
+                        self.table.push((start, 0, s));
+                        e
+                    })
+                    .collect();
+                let accept = self.new_state();
+                ends.into_iter()
+                    .for_each(|e| self.table.push((e, 0, accept)));
+                accept
+            }
+            Regex::Star(inner) | Regex::Plus(inner) | Regex::Optional(inner) => {
+                let (s, e) = self.build(inner);
+                let accept = self.new_state();
+                self.table.push((start, 0, s));
+                self.table.push((e, 0, accept));
+                if !matches!(regex, Regex::Optional(_)) {
+                    self.table.push((e, 0, s));
+                }
+                if !matches!(regex, Regex::Plus(_)) {
+                    self.table.push((start, 0, accept));
+                }
+                accept
+            }
+        };
+        (start, accept)
+    }
+}
+
+impl Automaton {
+    /// Build a non deterministic automaton recognizing a regular expression, by Thompson's
+    /// construction. Patterns are made of literal characters, `|` alternation, concatenation,
+    /// `*`, `+` and `?` repetition, and parentheses grouping - An operator character preceded by
+    /// a backslash is a literal. Each distinct literal character is a letter, numbered in
+    /// increasing character order and labelled with that character. Empty char transitions use
+    /// letter 0 of the table.
+    /// Returns an error if a group is left unclosed, a `)` closes no group, an operator repeats
+    /// nothing, or the pattern ends with a backslash.
+    pub fn from_regex(pattern: &str) -> Result<Automaton, RegexError> {
+        let mut parser = RegexParser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+        };
+        let regex = parser.parse_alt()?;
+        if let Some(c) = parser.chars.get(parser.pos) {
+            return Err(RegexError::UnexpectedChar {
+                position: parser.pos + 1,
+                found: *c,
+            });
+        }
+
+        // Collect the literals of the pattern, skipping the backslashes escaping them.
+        let mut letters = BTreeSet::new();
+        let mut escaped = false;
+        for c in &parser.chars {
+            if escaped || !OPERATORS.contains(*c) {
+                letters.insert(*c);
+            }
+            escaped = !escaped && *c == '\\';
+        }
+
+        let mut builder = ThompsonBuilder {
+            size: 0,
+            table: Vec::new(),
+            letters: letters.into_iter().collect(),
+        };
+        let (start, accept) = builder.build(&regex);
+        let mut ret = Automaton::new(
+            AutomatonType::NonDet,
+            builder.size,
+            builder.letters.len(),
+            builder.table,
+            vec![start],
+            vec![accept],
+        );
+        ret.alphabet_labels = Some(builder.letters.iter().map(|c| c.to_string()).collect());
+        Ok(ret)
+    }
+}
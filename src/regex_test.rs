@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use crate::automaton::{AlgorithmKind, Automaton, AutomatonType};
+    use crate::regex::RegexError;
+
+    /// Get the letters of a word written with the alphabet labels of an automaton.
+    fn letters_of(aut: &Automaton, word: &str) -> Vec<usize> {
+        let labels = aut.alphabet_labels.as_ref().unwrap();
+        word.chars()
+            .map(|c| labels.iter().position(|l| *l == c.to_string()).unwrap() + 1)
+            .collect()
+    }
+
+    #[test]
+    // Test whether (a|b)*abb gives the textbook 4 state minimal DFA, accepting exactly the words
+    // over a and b ending with abb.
+    fn test_from_regex_minimal_dfa() {
+        let nfa = Automaton::from_regex("(a|b)*abb").unwrap();
+        assert_eq!(nfa.automaton_type, AutomatonType::NonDet);
+        assert!(nfa.has_epsilon());
+        assert_eq!(nfa.alphabet, 2);
+        assert_eq!(
+            nfa.alphabet_labels,
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+
+        let dfa = nfa.determinized(AlgorithmKind::Sequential).minimized();
+        assert_eq!(dfa.size, 4);
+        let mut count = 0;
+        for word in dfa.words_up_to(6) {
+            assert_eq!(word[word.len() - 3..], letters_of(&dfa, "abb"));
+            count += 1;
+        }
+        // Words of length 3 to 6 ending with abb.
+        assert_eq!(count, 1 + 2 + 4 + 8);
+    }
+
+    #[test]
+    // Test whether alternation, repetition, grouping, escapes and the empty pattern recognize the
+    // words they should.
+    fn test_from_regex_operators() {
+        let cases = [
+            (
+                "ab+c?",
+                vec!["ab", "abb", "abc", "abbbc"],
+                vec!["a", "ac", "abcc"],
+            ),
+            (
+                "(ab|c)*",
+                vec!["", "ab", "cab", "abcc"],
+                vec!["a", "ba", "abb"],
+            ),
+            ("a(|b)", vec!["a", "ab"], vec!["", "b", "abb"]),
+            ("a\\*\\\\", vec!["a*\\"], vec!["a", "a*", "aa\\"]),
+            ("", vec![""], vec![]),
+        ];
+        for (pattern, accepted, rejected) in cases {
+            let aut = Automaton::from_regex(pattern).unwrap();
+            for word in accepted {
+                assert!(aut.accepts(&letters_of(&aut, word)), "{} {}", pattern, word);
+            }
+            for word in rejected {
+                assert!(
+                    !aut.accepts(&letters_of(&aut, word)),
+                    "{} {}",
+                    pattern,
+                    word
+                );
+            }
+        }
+    }
+
+    #[test]
+    // Test whether malformed patterns give an error at the offending character.
+    fn test_from_regex_errors() {
+        assert_eq!(
+            Automaton::from_regex("a(b|c").unwrap_err(),
+            RegexError::UnclosedGroup { position: 2 }
+        );
+        assert_eq!(
+            Automaton::from_regex("ab)c").unwrap_err(),
+            RegexError::UnexpectedChar {
+                position: 3,
+                found: ')'
+            }
+        );
+        assert_eq!(
+            Automaton::from_regex("a|*b").unwrap_err(),
+            RegexError::NothingToRepeat {
+                position: 3,
+                operator: '*'
+            }
+        );
+        assert_eq!(
+            Automaton::from_regex("ab\\").unwrap_err(),
+            RegexError::DanglingEscape { position: 3 }
+        );
+        assert_eq!(
+            Automaton::from_regex("(a").unwrap_err().to_string(),
+            "Group opened at character 1 is never closed!"
+        );
+    }
+}
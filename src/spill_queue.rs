@@ -0,0 +1,127 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{
+    automaton_sequential::Frontier,
+    ubig::{CompressedUbig, Ubig},
+};
+
+/// Counter making the spill file names of a process unique.
+static SPILL_FILE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// First-in first-out queue of sets of states that keeps at most `threshold` sets in memory,
+/// and spills the others compressed to a temporary file.
+pub struct SpillQueue {
+    threshold: usize,
+    memory: VecDeque<Ubig>,
+    path: PathBuf,
+    writer: BufWriter<File>,
+    reader: BufReader<File>,
+    spilled: usize,
+}
+
+impl SpillQueue {
+    /// Create a new spilling queue keeping at most `threshold` sets in memory.
+    pub fn new(threshold: usize) -> io::Result<SpillQueue> {
+        let path = std::env::temp_dir().join(format!(
+            "nfdeterminize-frontier-{}-{}",
+            process::id(),
+            SPILL_FILE_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let writer = BufWriter::new(
+            OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(&path)?,
+        );
+        let reader = BufReader::new(File::open(&path)?);
+        Ok(SpillQueue {
+            threshold: threshold.max(1),
+            memory: VecDeque::new(),
+            path,
+            writer,
+            reader,
+            spilled: 0,
+        })
+    }
+
+    /// Load the oldest spilled sets back into memory, up to the threshold.
+    fn reload(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+        while self.spilled > 0 && self.memory.len() < self.threshold {
+            let mut len = [0; 8];
+            self.reader.read_exact(&mut len)?;
+            let mut cnum = vec![0; u64::from_le_bytes(len) as usize];
+            self.reader.read_exact(&mut cnum)?;
+            self.memory.push_back(CompressedUbig { cnum }.decompress());
+            self.spilled -= 1;
+        }
+        Ok(())
+    }
+}
+
+impl Frontier for SpillQueue {
+    fn push_back(&mut self, set: Ubig) -> io::Result<()> {
+        // Sets go to disk as long as older sets are there, to keep the queue in order.
+        if self.spilled == 0 && self.memory.len() < self.threshold {
+            self.memory.push_back(set);
+        } else {
+            let cnum = set.compress().cnum;
+            self.writer.write_all(&(cnum.len() as u64).to_le_bytes())?;
+            self.writer.write_all(&cnum)?;
+            self.spilled += 1;
+        }
+        Ok(())
+    }
+
+    fn pop_front(&mut self) -> io::Result<Option<Ubig>> {
+        if self.memory.is_empty() && self.spilled > 0 {
+            self.reload()?;
+        }
+        Ok(self.memory.pop_front())
+    }
+}
+
+impl Drop for SpillQueue {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod spill_queue_tests {
+    use super::SpillQueue;
+    use crate::{automaton_sequential::Frontier, ubig::Ubig};
+
+    #[test]
+    fn test_spill_order() {
+        let mut queue = SpillQueue::new(2).unwrap();
+        let sets: Vec<Ubig> = (0..6)
+            .map(|i| {
+                let mut u = Ubig::new();
+                u.set_to(&(i * 5), true);
+                u
+            })
+            .collect();
+        sets[..4]
+            .iter()
+            .for_each(|u| queue.push_back(u.clone()).unwrap());
+        assert_eq!(queue.spilled, 2);
+        assert_eq!(queue.pop_front().unwrap(), Some(sets[0].clone()));
+        sets[4..]
+            .iter()
+            .for_each(|u| queue.push_back(u.clone()).unwrap());
+        assert_eq!(queue.spilled, 4);
+        for u in &sets[1..] {
+            assert_eq!(queue.pop_front().unwrap().as_ref(), Some(u));
+        }
+        assert_eq!(queue.pop_front().unwrap(), None);
+    }
+}
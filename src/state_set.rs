@@ -0,0 +1,105 @@
+use std::hash::{Hash, Hasher};
+
+use roaring::RoaringBitmap;
+
+use crate::ubig::{CompressedUbig, Ubig};
+
+/// Set of NFA states standing for a DFA state in the superset construction. Sets are only ever
+/// grown then cleared by the construction, so equal sets hash the same whatever order their
+/// states were added in.
+pub trait StateSet: Clone + Eq + Hash + Default + Send + Sync {
+    /// Compact form of a set, mapped to the DFA state it stands for.
+    type Key: Eq + Hash + Send + Sync;
+
+    /// Get the states of the set in increasing order.
+    fn get_seq(&self) -> Vec<usize>;
+    fn bit_at(&self, pos: &usize) -> bool;
+    fn set_to(&mut self, bit: &usize, val: bool);
+    fn flip(&mut self, bit: &usize);
+    /// Remove every state, keeping the allocated memory for reuse where possible.
+    fn clear(&mut self);
+    fn to_key(&self) -> Self::Key;
+}
+
+impl StateSet for Ubig {
+    type Key = CompressedUbig;
+
+    fn get_seq(&self) -> Vec<usize> {
+        Ubig::get_seq(self)
+    }
+
+    fn bit_at(&self, pos: &usize) -> bool {
+        Ubig::bit_at(self, pos)
+    }
+
+    fn set_to(&mut self, bit: &usize, val: bool) {
+        Ubig::set_to(self, bit, val)
+    }
+
+    fn flip(&mut self, bit: &usize) {
+        Ubig::flip(self, bit)
+    }
+
+    fn clear(&mut self) {
+        Ubig::clear(self)
+    }
+
+    fn to_key(&self) -> CompressedUbig {
+        self.clone().compress()
+    }
+}
+
+/// Set of states backed by a Roaring bitmap - Uses less memory than a `Ubig` when the states
+/// of a set are sparse among the states of a large NFA. States must fit in 32 bits.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RoaringStateSet(RoaringBitmap);
+
+impl RoaringStateSet {
+    fn to_u32(bit: &usize) -> u32 {
+        u32::try_from(*bit).expect("State does not fit in a RoaringStateSet!")
+    }
+}
+
+impl Eq for RoaringStateSet {}
+
+impl Hash for RoaringStateSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.len().hash(state);
+        self.0.iter().for_each(|s| s.hash(state));
+    }
+}
+
+impl StateSet for RoaringStateSet {
+    type Key = RoaringStateSet;
+
+    fn get_seq(&self) -> Vec<usize> {
+        self.0.iter().map(|s| s as usize).collect()
+    }
+
+    fn bit_at(&self, pos: &usize) -> bool {
+        u32::try_from(*pos).is_ok_and(|pos| self.0.contains(pos))
+    }
+
+    fn set_to(&mut self, bit: &usize, val: bool) {
+        if val {
+            self.0.insert(Self::to_u32(bit));
+        } else if let Ok(bit) = u32::try_from(*bit) {
+            self.0.remove(bit);
+        }
+    }
+
+    fn flip(&mut self, bit: &usize) {
+        let bit = Self::to_u32(bit);
+        if !self.0.remove(bit) {
+            self.0.insert(bit);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    fn to_key(&self) -> RoaringStateSet {
+        self.clone()
+    }
+}
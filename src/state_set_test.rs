@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use crate::automaton::{AlgorithmKind, Automaton, AutomatonType};
+    use crate::state_set::{RoaringStateSet, StateSet};
+    use crate::transition_graphs::{get_buffer_and_stack_aut, get_two_stack_aut};
+
+    #[test]
+    // Test whether a Roaring state set sets, flips and lists its states like a Ubig.
+    fn test_roaring_state_set() {
+        let mut set = RoaringStateSet::default();
+        set.set_to(&10000, true);
+        set.set_to(&3, true);
+        set.flip(&70);
+        assert!(set.bit_at(&10000) && set.bit_at(&3) && set.bit_at(&70));
+        assert_eq!(set.get_seq(), vec![3, 70, 10000]);
+
+        set.flip(&70);
+        set.set_to(&3, false);
+        set.set_to(&usize::MAX, false);
+        assert!(!set.bit_at(&70) && !set.bit_at(&usize::MAX));
+        assert_eq!(set.get_seq(), vec![10000]);
+
+        let mut other = RoaringStateSet::default();
+        other.set_to(&10000, true);
+        assert_eq!(set.to_key(), other.to_key());
+        set.clear();
+        assert!(set.get_seq().is_empty());
+    }
+
+    #[test]
+    // Test whether determinizing with Roaring state sets gives the same DFA as with Ubigs, both
+    // sequentially and multithreaded.
+    fn test_determinize_roaring() {
+        let empty_char_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 0, 2), (2, 2, 2), (2, 1, 0)],
+            vec![0],
+            vec![2],
+        );
+        for aut in [
+            empty_char_nd,
+            get_buffer_and_stack_aut(2, 3),
+            get_two_stack_aut(2, 2),
+        ] {
+            let sequential = aut.determinized(AlgorithmKind::Sequential);
+            let roaring = aut.determinized_with::<RoaringStateSet>(AlgorithmKind::Sequential);
+            assert_eq!(roaring.size, sequential.size);
+            assert_eq!(roaring.table, sequential.table);
+            assert_eq!(roaring.end, sequential.end);
+
+            let multithreaded =
+                aut.determinized_with::<RoaringStateSet>(AlgorithmKind::Multithreaded(4));
+            assert_eq!(
+                multithreaded.canonicalize().table,
+                sequential.canonicalize().table
+            );
+        }
+    }
+}
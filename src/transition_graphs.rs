@@ -1,6 +1,9 @@
 use std::collections::{HashMap, VecDeque};
 
-use crate::automaton::{Automaton, AutomatonType};
+use crate::{
+    automaton::{Automaton, AutomatonType},
+    rng::SplitMix64,
+};
 
 pub fn get_buffer_and_stack_aut(b: usize, n: usize) -> Automaton {
     #[derive(Debug, PartialEq, Eq, Hash, Clone)]
@@ -147,6 +150,23 @@ pub fn get_two_stack_aut(n1: usize, n2: usize) -> Automaton {
     )
 }
 
+/// Generate a random NFA from a seed, with transitions drawn uniformly over the states and over
+/// the letters of the alphabet and the empty char. Each state is a start or accept state with
+/// probability 1/2.
+pub fn get_random_nfa(size: usize, alphabet: usize, transitions: usize, seed: u64) -> Automaton {
+    let mut rng = SplitMix64::new(seed);
+    let table = if size == 0 {
+        vec![]
+    } else {
+        (0..transitions)
+            .map(|_| (rng.below(size), rng.below(alphabet + 1), rng.below(size)))
+            .collect()
+    };
+    let start = (0..size).filter(|_| rng.below(2) == 0).collect();
+    let end = (0..size).filter(|_| rng.below(2) == 0).collect();
+    Automaton::new(AutomatonType::NonDet, size, alphabet, table, start, end)
+}
+
 fn decrease_ranks(l: usize, a: usize) -> usize {
     if l > a {
         l - 1
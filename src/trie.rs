@@ -0,0 +1,82 @@
+use crate::{automaton_sequential::NumMapper, state_set::StateSet};
+
+/// Trie mapping sorted sequences of states to ids, numbered in insertion order - Sequences
+/// sharing a prefix share the nodes of that prefix, and lookups need no compression.
+#[derive(Debug)]
+pub struct NodeTrie {
+    /// Children of each node as (state, node) pairs sorted by state. Node 0 is the root.
+    children: Vec<Vec<(usize, usize)>>,
+    ids: Vec<Option<usize>>,
+    len: usize,
+}
+
+impl Default for NodeTrie {
+    fn default() -> Self {
+        NodeTrie::new()
+    }
+}
+
+impl NodeTrie {
+    pub fn new() -> NodeTrie {
+        NodeTrie {
+            children: vec![vec![]],
+            ids: vec![None],
+            len: 0,
+        }
+    }
+
+    /// Get the number of sequences in the trie.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the id of a sequence, if it is in the trie.
+    pub fn get(&self, seq: &[usize]) -> Option<usize> {
+        let mut node = 0;
+        for s in seq {
+            let children = &self.children[node];
+            node = children[children.binary_search_by_key(s, |c| c.0).ok()?].1;
+        }
+        self.ids[node]
+    }
+
+    /// Get the id of a sequence, inserting it with the next id if it is new. Returns whether it
+    /// is new.
+    pub fn get_or_insert(&mut self, seq: &[usize]) -> (usize, bool) {
+        let mut node = 0;
+        for s in seq {
+            node = match self.children[node].binary_search_by_key(s, |c| c.0) {
+                Ok(i) => self.children[node][i].1,
+                Err(i) => {
+                    let child = self.children.len();
+                    self.children.push(vec![]);
+                    self.ids.push(None);
+                    self.children[node].insert(i, (*s, child));
+                    child
+                }
+            };
+        }
+        match self.ids[node] {
+            Some(id) => (id, false),
+            None => {
+                self.ids[node] = Some(self.len);
+                self.len += 1;
+                (self.len - 1, true)
+            }
+        }
+    }
+}
+
+impl<S: StateSet> NumMapper<S> for NodeTrie {
+    fn get_or_insert(&mut self, set: &S) -> (usize, bool) {
+        NodeTrie::get_or_insert(self, &set.get_seq())
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use crate::automaton::{AlgorithmKind, Automaton, AutomatonType};
+    use crate::transition_graphs::{get_buffer_and_stack_aut, get_two_stack_aut};
+    use crate::trie::NodeTrie;
+
+    #[test]
+    // Test whether sequences are numbered in insertion order, including prefixes of each other
+    // and the empty sequence, and looked up without being inserted again.
+    fn test_node_trie() {
+        let mut trie = NodeTrie::new();
+        assert!(trie.is_empty());
+        assert_eq!(trie.get_or_insert(&[1, 3, 5]), (0, true));
+        assert_eq!(trie.get_or_insert(&[1, 3]), (1, true));
+        assert_eq!(trie.get_or_insert(&[]), (2, true));
+        assert_eq!(trie.get_or_insert(&[0, 3]), (3, true));
+        assert_eq!(trie.get_or_insert(&[1, 3, 5]), (0, false));
+        assert_eq!(trie.len(), 4);
+
+        assert_eq!(trie.get(&[1, 3]), Some(1));
+        assert_eq!(trie.get(&[0, 3]), Some(3));
+        assert_eq!(trie.get(&[1]), None);
+        assert_eq!(trie.get(&[1, 3, 5, 7]), None);
+    }
+
+    #[test]
+    // Test whether numbering subsets with the trie gives the same DFA as the sequential
+    // determinization.
+    fn test_determinize_trie() {
+        let empty_char_nd = Automaton::new(
+            AutomatonType::NonDet,
+            3,
+            2,
+            vec![(0, 1, 1), (1, 0, 2), (2, 2, 2), (2, 1, 0)],
+            vec![0],
+            vec![2],
+        );
+        for aut in [
+            empty_char_nd,
+            get_buffer_and_stack_aut(2, 3),
+            get_two_stack_aut(2, 2),
+        ] {
+            let sequential = aut.determinized(AlgorithmKind::Sequential);
+            let trie = aut.determinized_trie();
+            assert_eq!(trie.size, sequential.size);
+            assert_eq!(trie.table, sequential.table);
+            assert_eq!(trie.start, sequential.start);
+            assert_eq!(trie.end, sequential.end);
+        }
+    }
+}
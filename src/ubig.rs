@@ -1,7 +1,7 @@
 use lz4_flex::{compress_prepend_size, decompress_size_prepended};
 use std::hash::{Hash, Hasher};
 
-#[derive(Debug, Clone, Eq)]
+#[derive(Debug, Clone, Default, Eq)]
 pub struct Ubig {
     pub num: Vec<u8>,
 }
@@ -18,8 +18,10 @@ impl Hash for CompressedUbig {
 }
 
 impl Hash for Ubig {
+    // Trailing zero bytes are ignored, as by equality.
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.num.hash(state);
+        let len = self.num.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+        self.num[..len].hash(state);
     }
 }
 
@@ -35,7 +37,7 @@ impl PartialEq for Ubig {
         }
         for bit in 0..a.num.len() {
             if bit >= b.num.len() {
-                if a.num[bit] == 1 {
+                if a.num[bit] != 0 {
                     return false;
                 }
             } else if a.num[bit] != b.num[bit] {
@@ -47,7 +49,7 @@ impl PartialEq for Ubig {
 }
 
 impl CompressedUbig {
-    fn decompress(self) -> Ubig {
+    pub fn decompress(self) -> Ubig {
         Ubig {
             num: decompress_size_prepended(self.cnum.as_slice().clone()).unwrap(),
         }
@@ -73,6 +75,23 @@ impl Ubig {
         return ret;
     }
 
+    /// Unset all bits, keeping the allocated bytes for reuse.
+    pub fn clear(&mut self) {
+        self.num.clear();
+    }
+
+    /// Check whether every bit set in this Ubig is also set in another - Compared 64 bits at a time.
+    pub fn is_subset_of(&self, other: &Ubig) -> bool {
+        let word = |num: &Vec<u8>, i: usize| -> u64 {
+            let mut bytes = [0u8; 8];
+            if let Some(chunk) = num.chunks(8).nth(i) {
+                bytes[..chunk.len()].copy_from_slice(chunk);
+            }
+            u64::from_le_bytes(bytes)
+        };
+        (0..self.num.len().div_ceil(8)).all(|i| word(&self.num, i) & !word(&other.num, i) == 0)
+    }
+
     pub fn bit_at(&self, pos: &usize) -> bool {
         if *pos < self.num.len() * 8 {
             return (self.num[pos / 8] >> (pos % 8)) & 1 == 1;
@@ -100,20 +119,19 @@ impl Ubig {
             } else {
                 self.num[pos] = self.num[pos] & (0xFF ^ (1 << bit % 8));
             }
-        } else {
+        } else if val {
             self.extend(bit);
-            self.flip(bit);
+            self.set_to(bit, val);
         }
     }
 
-    // Extend the vector of bits of the array to required size.
-    fn extend(&mut self, new_size: &usize) {
-        let mut size_incr = new_size - self.num.len();
-        while size_incr > 8 {
-            self.num.push(0);
-            size_incr -= 8;
+    // Extend the vector of bytes of the array with zeros, so that it holds the given bit.
+    // The length then only depends on the highest bit set, whatever order bits are set in.
+    fn extend(&mut self, bit: &usize) {
+        let len = bit / 8 + 1;
+        if len > self.num.len() {
+            self.num.resize(len, 0);
         }
-        self.num.push(0);
     }
 
     pub fn compress(self) -> CompressedUbig {
@@ -210,6 +228,47 @@ mod ubig_tests {
         assert_eq!(test_ubig.bit_at(&11), false);
     }
 
+    #[test]
+    fn test_extend() {
+        // Setting a far bit on a fresh Ubig only allocates the bytes needed to hold it.
+        let mut far = Ubig::new();
+        far.set_to(&10000, true);
+        assert_eq!(far.num.len(), 10000 / 8 + 1);
+        assert!(far.bit_at(&10000));
+        assert_eq!(far.get_seq(), vec![10000]);
+
+        // The same bits set in any order give the same bytes.
+        let mut rev = Ubig::new();
+        rev.flip(&10000);
+        rev.flip(&50);
+        assert_eq!(rev.num, Ubig::from_seq(&vec![50, 10000]).num);
+
+        // Unsetting a bit out of range leaves the Ubig untouched.
+        let mut unset = Ubig::new();
+        unset.set_to(&100, false);
+        assert!(unset.num.is_empty());
+        assert!(!unset.bit_at(&100));
+    }
+
+    #[test]
+    fn test_is_subset_of() {
+        let small = Ubig::from_seq(&vec![1, 9]);
+        let large = Ubig::from_seq(&vec![1, 3, 9, 70]);
+        let disjoint = Ubig::from_seq(&vec![2, 10]);
+        assert!(small.is_subset_of(&large));
+        assert!(!large.is_subset_of(&small));
+        assert!(!small.is_subset_of(&disjoint));
+        assert!(Ubig::new().is_subset_of(&small));
+        assert!(small.is_subset_of(&small));
+
+        // Trailing zero bytes do not count as set bits on either side.
+        let mut trailing = Ubig::from_seq(&vec![1, 9, 100]);
+        trailing.set_to(&100, false);
+        assert!(trailing.is_subset_of(&small));
+        assert!(small.is_subset_of(&trailing));
+        assert!(!Ubig::from_bit(&70).is_subset_of(&small));
+    }
+
     #[test]
     fn test_compress_decompress() {
         let test_seq = vec![1, 8, 24, 32, 121, 12389, 120321];